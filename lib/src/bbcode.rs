@@ -0,0 +1,141 @@
+//! Rendering highlighted code as BBCode for forums.
+use std::io;
+
+use typst_syntax::Tag;
+
+use crate::{
+    color::{hex, HexFormat},
+    render::Renderer,
+    Style,
+};
+
+/// Renders styles as `[color]`, `[b]`, `[i]` and `[u]` tags and links as `[url]` tags.
+///
+/// BBCode has no way to escape brackets, so every literal `[` is followed by an empty
+/// `[b][/b]` tag, which prevents forums from reading it as the start of a tag.
+/// Background colors and dimmed text are not supported by most forums and are left out.
+///
+/// Unlike [`Highlighter::highlight_bbcode`], this doesn't wrap the output in another tag.
+///
+/// [`Highlighter::highlight_bbcode`]: crate::Highlighter::highlight_bbcode
+pub struct BbcodeRenderer<W> {
+    inner: W,
+    style: Style,
+}
+
+impl<W: io::Write> BbcodeRenderer<W> {
+    /// Create a renderer writing to the given writer.
+    pub fn new(writer: W) -> BbcodeRenderer<W> {
+        BbcodeRenderer {
+            inner: writer,
+            style: Style::new(),
+        }
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: io::Write> Renderer for BbcodeRenderer<W> {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        self.style = *style;
+        if let Some(fg) = style.fg {
+            write!(self.inner, "[color={}]", hex(fg, HexFormat::Css))?;
+        }
+        if style.bold {
+            write!(self.inner, "[b]")?;
+        }
+        if style.italic {
+            write!(self.inner, "[i]")?;
+        }
+        if style.underline {
+            write!(self.inner, "[u]")?;
+        }
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        let style = std::mem::replace(&mut self.style, Style::new());
+        if style.underline {
+            write!(self.inner, "[/u]")?;
+        }
+        if style.italic {
+            write!(self.inner, "[/i]")?;
+        }
+        if style.bold {
+            write!(self.inner, "[/b]")?;
+        }
+        if style.fg.is_some() {
+            write!(self.inner, "[/color]")?;
+        }
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        let mut pieces = text.split('[');
+        if let Some(first) = pieces.next() {
+            write!(self.inner, "{first}")?;
+        }
+        for piece in pieces {
+            write!(self.inner, "[[b][/b]{piece}")?;
+        }
+        Ok(())
+    }
+
+    fn start_link(&mut self, url: &str) -> io::Result<()> {
+        // Brackets would end the tag, but are equivalent to their percent-encoding in URLs.
+        let url = url.replace('[', "%5B").replace(']', "%5D");
+        write!(self.inner, "[url={url}]")
+    }
+
+    fn end_link(&mut self) -> io::Result<()> {
+        write!(self.inner, "[/url]")
+    }
+}
+
+/// The opening and closing tags for a wrapper like `code` or `font=monospace`.
+pub(crate) fn wrapper_tags(wrapper: &str) -> (String, String) {
+    let name = wrapper
+        .split(['=', ' '])
+        .next()
+        .expect("split always returns an item");
+    (format!("[{wrapper}]"), format!("[/{name}]"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Highlighter;
+
+    #[test]
+    fn test_highlight_bbcode() {
+        let output = Highlighter::default()
+            .highlight_bbcode("*a* [b]", Some("font=monospace"))
+            .unwrap();
+        assert_eq!(
+            output,
+            "[font=monospace][color=#cdcd00][b]*[/b][/color]a* [[b][/b]b][/font]"
+        );
+
+        let output = Highlighter::default()
+            .highlight_bbcode("https://typst.app", None)
+            .unwrap();
+        assert_eq!(
+            output,
+            "[url=https://typst.app][color=#0000ee][u]https://typst.app[/u][/color][/url]"
+        );
+    }
+
+    #[test]
+    fn test_bbcode_link_brackets() {
+        let output = Highlighter::default()
+            .highlight_bbcode("https://a.b/[x]y", None)
+            .unwrap();
+        assert_eq!(
+            output,
+            "[url=https://a.b/%5Bx%5Dy][color=#0000ee][u]https://a.b/[[b][/b]x]y\
+             [/u][/color][/url]"
+        );
+    }
+}
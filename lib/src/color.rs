@@ -0,0 +1,210 @@
+//! Adapting colors to what the output is able to display.
+use termcolor::{Color, ColorSpec};
+
+/// How many colors the output is able to display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// The eight basic colors.
+    Ansi8,
+    /// The eight basic colors and their bright variants.
+    Ansi16,
+    /// The 256-color palette.
+    Ansi256,
+    /// 24-bit RGB colors.
+    TrueColor,
+}
+
+/// The basic colors in the order of their ANSI palette indices.
+const BASIC_COLORS: [Color; 8] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// The RGB value of a color, for output formats without a palette.
+///
+/// Palette colors use the default palette of xterm.
+pub(crate) fn to_rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Ansi256(index) => ansi_colours::rgb_from_ansi256(index),
+        color => {
+            let index = BASIC_COLORS.iter().position(|&basic| basic == color);
+            ansi_colours::rgb_from_ansi256(index.unwrap_or(0) as u8)
+        }
+    }
+}
+
+/// How [`hex`] writes the hex code of a color.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum HexFormat {
+    /// `#rrggbb`, as used by CSS and most markup languages.
+    Css,
+    /// `RRGGBB`, as used by the `HTML` color model of `xcolor`.
+    Latex,
+}
+
+/// The hex code of a color, with palette colors converted by [`to_rgb`].
+pub(crate) fn hex(color: Color, format: HexFormat) -> String {
+    let (r, g, b) = to_rgb(color);
+    match format {
+        HexFormat::Css => format!("#{r:02x}{g:02x}{b:02x}"),
+        HexFormat::Latex => format!("{r:02X}{g:02X}{b:02X}"),
+    }
+}
+
+impl ColorDepth {
+    /// Convert a color into the closest one that can be displayed at this color depth.
+    ///
+    /// Colors that can already be displayed are returned unchanged.
+    pub fn quantize(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::TrueColor, color) => color,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => {
+                Color::Ansi256(ansi_colours::ansi256_from_rgb((r, g, b)))
+            }
+            (ColorDepth::Ansi256, color) => color,
+            (_, Color::Ansi256(index)) if index < 8 => BASIC_COLORS[index as usize],
+            (ColorDepth::Ansi16, Color::Ansi256(index)) if index < 16 => color,
+            (ColorDepth::Ansi8, Color::Ansi256(index)) if index < 16 => {
+                BASIC_COLORS[index as usize - 8]
+            }
+            (_, Color::Ansi256(index)) => self.quantize_rgb(ansi_colours::rgb_from_ansi256(index)),
+            (_, Color::Rgb(r, g, b)) => self.quantize_rgb((r, g, b)),
+            (_, color) => color,
+        }
+    }
+
+    /// Convert all colors of a color specification using [`ColorDepth::quantize`].
+    pub(crate) fn quantize_spec(self, spec: &ColorSpec) -> ColorSpec {
+        let mut spec = spec.clone();
+        spec.set_fg(spec.fg().map(|&color| self.quantize(color)));
+        spec.set_bg(spec.bg().map(|&color| self.quantize(color)));
+        spec
+    }
+
+    /// The index of the basic color whose bright variant a color is converted to,
+    /// if it is one of the bright colors at this color depth.
+    ///
+    /// Terminals limited to sixteen colors only understand these as SGR 90–97 and 100–107,
+    /// not as indices 8 to 15 of the 256-color palette.
+    pub(crate) fn bright_index(self, color: Color) -> Option<u8> {
+        match self.quantize(color) {
+            Color::Ansi256(index) if self <= ColorDepth::Ansi16 && (8..16).contains(&index) => {
+                Some(index - 8)
+            }
+            _ => None,
+        }
+    }
+
+    /// Map an RGB color onto the eight or sixteen color palette.
+    ///
+    /// The nearest palette color by distance is often a poor match, because the palette
+    /// is so coarse: a blue-ish gray would become cyan.
+    /// Instead, grays are mapped by their lightness and other colors by their hue.
+    fn quantize_rgb(self, (r, g, b): (u8, u8, u8)) -> Color {
+        let bright = self >= ColorDepth::Ansi16;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let chroma = max - min;
+        let lightness = (u16::from(max) + u16::from(min)) / 2;
+
+        if chroma < 32 {
+            return match lightness {
+                0..48 if bright => Color::Black,
+                0..144 if bright => Color::Ansi256(8),
+                0..144 => Color::Black,
+                144..224 if bright => Color::White,
+                _ if bright => Color::Ansi256(15),
+                _ => Color::White,
+            };
+        }
+
+        let (r, g, b, max, chroma) = (
+            f32::from(r),
+            f32::from(g),
+            f32::from(b),
+            f32::from(max),
+            f32::from(chroma),
+        );
+        let hue = if max == r {
+            ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / chroma + 2.0
+        } else {
+            (r - g) / chroma + 4.0
+        };
+        // Red, yellow, green, cyan, blue, magenta.
+        let index = [1, 3, 2, 6, 4, 5][hue.round() as usize % 6];
+        if bright && lightness >= 160 {
+            Color::Ansi256(index + 8)
+        } else {
+            BASIC_COLORS[index as usize]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantize() {
+        let rgb = Color::Rgb(0xdc, 0x32, 0x2f);
+        assert_eq!(ColorDepth::TrueColor.quantize(rgb), rgb);
+        assert_eq!(ColorDepth::Ansi256.quantize(rgb), Color::Ansi256(167));
+        assert_eq!(ColorDepth::Ansi16.quantize(rgb), Color::Red);
+        assert_eq!(ColorDepth::Ansi8.quantize(rgb), Color::Red);
+
+        let gray = Color::Rgb(0x58, 0x6e, 0x75);
+        assert_eq!(ColorDepth::Ansi16.quantize(gray), Color::Ansi256(8));
+        assert_eq!(ColorDepth::Ansi8.quantize(gray), Color::Black);
+
+        assert_eq!(
+            ColorDepth::Ansi16.quantize(Color::Ansi256(3)),
+            Color::Yellow
+        );
+        assert_eq!(ColorDepth::Ansi8.quantize(Color::Ansi256(12)), Color::Blue);
+        assert_eq!(
+            ColorDepth::Ansi16.bright_index(Color::Ansi256(231)),
+            Some(7)
+        );
+        assert_eq!(ColorDepth::Ansi16.bright_index(Color::Ansi256(3)), None);
+        assert_eq!(ColorDepth::Ansi256.bright_index(Color::Ansi256(12)), None);
+        assert_eq!(ColorDepth::Ansi8.quantize(Color::Ansi256(46)), Color::Green);
+    }
+
+    #[test]
+    fn test_bright_colors() {
+        let highlight = |color_depth| {
+            crate::Highlighter::default()
+                .with_theme(crate::Theme::HIGH_CONTRAST)
+                .with_color_depth(color_depth)
+                .highlight("= A\n```\nb```")
+                .unwrap()
+        };
+        assert_eq!(
+            highlight(ColorDepth::Ansi16),
+            "\x1b[0m\x1b[1m\x1b[4m\x1b[96m=\x1b[0m A\n\x1b[0m\x1b[97m```\nb```"
+        );
+        assert_eq!(
+            highlight(ColorDepth::Ansi8),
+            "\x1b[0m\x1b[1m\x1b[4m\x1b[36m=\x1b[0m A\n\x1b[0m\x1b[37m```\nb```"
+        );
+    }
+
+    #[test]
+    fn test_true_color_output() {
+        let output = crate::Highlighter::default()
+            .with_theme(crate::Theme::SOLARIZED)
+            .with_color_depth(ColorDepth::TrueColor)
+            .highlight("#let")
+            .unwrap();
+        assert_eq!(output, "\x1b[0m\x1b[38;2;133;153;0m#let");
+    }
+}
@@ -0,0 +1,198 @@
+//! Guessing the language of raw blocks without a language tag.
+use syntect::parsing::SyntaxSet;
+
+/// Signals hinting at a language, each with how much it adds to the confidence.
+///
+/// A signal starting with a newline only matches at the start of a line.
+const HEURISTICS: &[(&str, &[(&str, f32)])] = &[
+    (
+        "typ",
+        &[
+            ("\n#set ", 0.5),
+            ("\n#show ", 0.5),
+            ("\n#let ", 0.4),
+            ("\n#import ", 0.5),
+            ("\n= ", 0.2),
+            ("#{", 0.2),
+        ],
+    ),
+    (
+        "rust",
+        &[
+            ("fn ", 0.2),
+            ("\nfn main()", 0.5),
+            ("let mut ", 0.4),
+            ("\nuse ", 0.2),
+            ("impl ", 0.3),
+            ("#[derive(", 0.5),
+            ("println!(", 0.5),
+            ("-> ", 0.1),
+            ("::", 0.1),
+        ],
+    ),
+    (
+        "python",
+        &[
+            ("\ndef ", 0.4),
+            ("\nimport ", 0.2),
+            ("\nfrom ", 0.2),
+            ("elif ", 0.4),
+            ("self.", 0.2),
+            ("print(", 0.2),
+            ("__init__", 0.4),
+        ],
+    ),
+    (
+        "js",
+        &[
+            ("function ", 0.3),
+            ("const ", 0.2),
+            ("=> ", 0.2),
+            ("console.log(", 0.5),
+            ("===", 0.3),
+            ("require(", 0.3),
+        ],
+    ),
+    (
+        "c",
+        &[
+            ("\n#include ", 0.5),
+            ("int main(", 0.4),
+            ("printf(", 0.3),
+            ("\n#define ", 0.3),
+        ],
+    ),
+    (
+        "bash",
+        &[
+            ("\n$ ", 0.4),
+            ("\necho ", 0.3),
+            ("\nsudo ", 0.4),
+            ("\nexport ", 0.3),
+            ("\nfi\n", 0.4),
+            ("\ncd ", 0.3),
+            ("\ncargo ", 0.4),
+        ],
+    ),
+    (
+        "html",
+        &[
+            ("<!DOCTYPE", 0.6),
+            ("<html", 0.5),
+            ("<div", 0.3),
+            ("</", 0.2),
+        ],
+    ),
+    (
+        "sql",
+        &[
+            ("SELECT ", 0.3),
+            ("\nFROM ", 0.3),
+            ("WHERE ", 0.2),
+            ("INSERT INTO ", 0.5),
+        ],
+    ),
+    (
+        "toml",
+        &[
+            ("\n[package]", 0.6),
+            ("\n[dependencies]", 0.6),
+            ("\n[[", 0.2),
+        ],
+    ),
+];
+
+/// Guess the language of some code, returning a language tag and how confident the guess is.
+///
+/// The confidence is between `0.0` and `1.0`.
+/// Shebangs and other first lines known to syntect are most reliable,
+/// after that the content is checked for typical signals of a few common languages.
+pub(crate) fn guess_lang(code: &str, syntax_sets: &[&SyntaxSet]) -> Option<(String, f32)> {
+    let code = code.trim_start();
+    let first_line = code.lines().next()?;
+    let syntax = syntax_sets
+        .iter()
+        .find_map(|syntax_set| syntax_set.find_syntax_by_first_line(first_line));
+    if let Some(syntax) = syntax {
+        let confidence = if first_line.starts_with("#!") {
+            1.0
+        } else {
+            0.9
+        };
+        return Some((syntax.name.clone(), confidence));
+    }
+
+    if looks_like_json(code) {
+        return Some(("json".to_owned(), 0.8));
+    }
+
+    let haystack = format!("\n{code}");
+    HEURISTICS
+        .iter()
+        .map(|&(lang, signals)| {
+            let score: f32 = signals
+                .iter()
+                .filter(|(signal, _)| haystack.contains(signal))
+                .map(|&(_, weight)| weight)
+                .sum();
+            (lang, score.min(1.0))
+        })
+        .filter(|&(_, score)| score > 0.0)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(lang, score)| (lang.to_owned(), score))
+}
+
+fn looks_like_json(code: &str) -> bool {
+    let code = code.trim_end();
+    let object = code.starts_with('{') && code.ends_with('}') && code.contains("\":");
+    let array = code.starts_with('[') && code.ends_with(']') && code.contains('"');
+    object || array
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Highlighter;
+
+    #[test]
+    fn test_guess_lang() {
+        let syntax_set = two_face::syntax::extra_newlines();
+        let guess = |code| guess_lang(code, &[&syntax_set]);
+
+        assert_eq!(
+            guess("\n#!/bin/sh\necho hi\n"),
+            Some(("Bourne Again Shell (bash)".to_owned(), 1.0))
+        );
+        assert_eq!(guess("{\"a\": 1}"), Some(("json".to_owned(), 0.8)));
+        assert_eq!(
+            guess("fn main() {\n    println!(\"hi\");\n}").map(|(lang, _)| lang),
+            Some("rust".to_owned())
+        );
+        assert_eq!(
+            guess("#set text(red)\n#show: it => it").map(|(lang, _)| lang),
+            Some("typ".to_owned())
+        );
+        assert_eq!(guess("just some words"), None);
+        assert_eq!(guess(""), None);
+    }
+
+    #[test]
+    fn test_lang_guessing() {
+        // Whether guessing changed the output, i.e. whether the code was highlighted.
+        let highlighted = |min_confidence, input: &str| {
+            let guessed = Highlighter::default()
+                .with_lang_guessing(Some(min_confidence))
+                .highlight(input)
+                .unwrap();
+            guessed != Highlighter::default().highlight(input).unwrap()
+        };
+
+        // The heuristics give this a confidence of 0.4 for Rust.
+        let code = "let mut x = 1;";
+        assert!(highlighted(0.4, &format!("```\n{code}\n```")));
+        assert!(!highlighted(0.5, &format!("```\n{code}\n```")));
+        // Tagged blocks and inline raw text are never guessed.
+        assert!(!highlighted(0.4, &format!("```foo\n{code}\n```")));
+        assert!(!highlighted(0.4, &format!("`{code}`")));
+    }
+}
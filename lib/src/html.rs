@@ -0,0 +1,154 @@
+//! Rendering highlighted code as HTML.
+use std::{fmt::Write as _, io};
+
+use typst_syntax::Tag;
+
+use crate::{
+    color::{hex, HexFormat},
+    render::Renderer,
+    Style, Theme,
+};
+
+/// How HTML output applies styles to the highlighted code.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum HtmlStyle {
+    /// Each span carries its style in a `style` attribute.
+    #[default]
+    Inline,
+    /// Each span of a Typst token carries the CSS class of its tag, e.g. `typ-key`,
+    /// and is styled by a stylesheet.
+    /// Raw blocks highlighted by syntect still use inline styles.
+    Classes,
+}
+
+/// Renders styles as `<span>` elements and links as `<a>` elements.
+///
+/// Unlike [`Highlighter::highlight_html`], this doesn't wrap the output in `<pre><code>`.
+///
+/// [`Highlighter::highlight_html`]: crate::Highlighter::highlight_html
+pub struct HtmlRenderer<W> {
+    inner: W,
+    html_style: HtmlStyle,
+}
+
+impl<W: io::Write> HtmlRenderer<W> {
+    /// Create a renderer writing to the given writer.
+    pub fn new(writer: W, html_style: HtmlStyle) -> HtmlRenderer<W> {
+        HtmlRenderer {
+            inner: writer,
+            html_style,
+        }
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: io::Write> Renderer for HtmlRenderer<W> {
+    fn start_style(&mut self, style: &Style, tag: Option<Tag>) -> io::Result<()> {
+        match (self.html_style, tag) {
+            (HtmlStyle::Classes, Some(tag)) => {
+                write!(self.inner, "<span class=\"{}\">", tag.css_class())
+            }
+            _ => write!(self.inner, "<span style=\"{}\">", css(style)),
+        }
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        write!(self.inner, "</span>")
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        escape(&mut self.inner, text)
+    }
+
+    fn start_link(&mut self, url: &str) -> io::Result<()> {
+        write!(self.inner, "<a href=\"")?;
+        escape(&mut self.inner, url)?;
+        write!(self.inner, "\">")
+    }
+
+    fn end_link(&mut self) -> io::Result<()> {
+        write!(self.inner, "</a>")
+    }
+}
+
+/// Write text with the characters that are special in HTML escaped.
+pub(crate) fn escape<W: io::Write>(out: &mut W, text: &str) -> io::Result<()> {
+    let mut rest = text;
+    while let Some(index) = rest.find(['&', '<', '>', '"']) {
+        let escaped = match rest.as_bytes()[index] {
+            b'&' => "&amp;",
+            b'<' => "&lt;",
+            b'>' => "&gt;",
+            _ => "&quot;",
+        };
+        write!(out, "{}{escaped}", &rest[..index])?;
+        rest = &rest[index + 1..];
+    }
+    write!(out, "{rest}")
+}
+
+/// Generate CSS rules for the classes used by [`HtmlStyle::Classes`].
+pub(crate) fn stylesheet(theme: &Theme) -> String {
+    let mut rules = String::new();
+    for &tag in Tag::LIST {
+        let style = theme.get(tag);
+        if !style.is_plain() {
+            writeln!(rules, ".{} {{ {} }}", tag.css_class(), css(style)).unwrap();
+        }
+    }
+    rules
+}
+
+/// The CSS declarations for a style.
+fn css(style: &Style) -> String {
+    let mut declarations = Vec::new();
+    if let Some(fg) = style.fg {
+        declarations.push(format!("color: {}", hex(fg, HexFormat::Css)));
+    }
+    if let Some(bg) = style.bg {
+        declarations.push(format!("background-color: {}", hex(bg, HexFormat::Css)));
+    }
+    if style.bold {
+        declarations.push("font-weight: bold".to_owned());
+    }
+    if style.italic {
+        declarations.push("font-style: italic".to_owned());
+    }
+    if style.underline {
+        declarations.push("text-decoration: underline".to_owned());
+    }
+    if style.dimmed {
+        declarations.push("opacity: 0.6".to_owned());
+    }
+    declarations.join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Highlighter, HtmlStyle};
+
+    #[test]
+    fn test_highlight_html() {
+        let input = "a < b & *c*";
+        let output = Highlighter::default()
+            .highlight_html(input, HtmlStyle::Inline)
+            .unwrap();
+        assert_eq!(
+            output,
+            "<pre><code>a &lt; b &amp; \
+             <span style=\"color: #cdcd00; font-weight: bold\">*</span>c*</code></pre>"
+        );
+
+        let output = Highlighter::default()
+            .highlight_html(input, HtmlStyle::Classes)
+            .unwrap();
+        assert!(output.contains("<span class=\"typ-strong\">*</span>"));
+        assert!(Highlighter::default()
+            .html_stylesheet()
+            .contains(".typ-strong { "));
+    }
+}
@@ -0,0 +1,165 @@
+//! Rendering highlighted code using mIRC formatting codes.
+use std::io;
+
+use typst_syntax::Tag;
+
+use crate::{color::to_rgb, render::Renderer, Style};
+
+const BOLD: &str = "\x02";
+const COLOR: &str = "\x03";
+const ITALIC: &str = "\x1D";
+const UNDERLINE: &str = "\x1F";
+const RESET: &str = "\x0F";
+
+/// Control codes that change the formatting, including ones for colors in hex,
+/// monospace, reversed and struck-through text, which aren't used here.
+const FORMATTING_CODES: [char; 9] = [
+    '\x02', '\x03', '\x04', '\x0F', '\x11', '\x16', '\x1D', '\x1E', '\x1F',
+];
+
+/// The number of the default color, which keeps the foreground when only setting a background.
+const DEFAULT_COLOR: u8 = 99;
+
+/// The 16 colors of mIRC in the order of their numbers.
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0xff, 0xff, 0xff),
+    (0x00, 0x00, 0x00),
+    (0x00, 0x00, 0x7f),
+    (0x00, 0x93, 0x00),
+    (0xff, 0x00, 0x00),
+    (0x7f, 0x00, 0x00),
+    (0x9c, 0x00, 0x9c),
+    (0xfc, 0x7f, 0x00),
+    (0xff, 0xff, 0x00),
+    (0x00, 0xfc, 0x00),
+    (0x00, 0x93, 0x93),
+    (0x00, 0xff, 0xff),
+    (0x00, 0x00, 0xfc),
+    (0xff, 0x00, 0xff),
+    (0x7f, 0x7f, 0x7f),
+    (0xd2, 0xd2, 0xd2),
+];
+
+/// Renders styles as mIRC control codes, using the closest of the 16 IRC colors.
+///
+/// Every line is sent as a separate message and clients reset the formatting
+/// at the start of each message, so styles are applied again after a newline.
+/// IRC has no dimmed text, so it is rendered normally.
+/// Formatting codes in the text itself are removed, so they can't change its formatting.
+pub struct IrcRenderer<W> {
+    inner: W,
+    style: Option<Style>,
+    open: bool,
+}
+
+impl<W: io::Write> IrcRenderer<W> {
+    /// Create a renderer writing to the given writer.
+    pub fn new(writer: W) -> IrcRenderer<W> {
+        IrcRenderer {
+            inner: writer,
+            style: None,
+            open: false,
+        }
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn open(&mut self, text: &str) -> io::Result<()> {
+        let Some(style) = self.style.filter(|_| !self.open) else {
+            return Ok(());
+        };
+        self.open = true;
+        match (style.fg, style.bg) {
+            (Some(fg), Some(bg)) => write!(self.inner, "{COLOR}{:02},{:02}", irc(fg), irc(bg))?,
+            (None, Some(bg)) => write!(self.inner, "{COLOR}{DEFAULT_COLOR},{:02}", irc(bg))?,
+            (Some(fg), None) => {
+                write!(self.inner, "{COLOR}{:02}", irc(fg))?;
+                // A comma followed by digits would be read as a background color,
+                // which an empty pair of bold codes prevents.
+                if text.starts_with(',') {
+                    write!(self.inner, "{BOLD}{BOLD}")?;
+                }
+            }
+            (None, None) => {}
+        }
+        if style.bold {
+            write!(self.inner, "{BOLD}")?;
+        }
+        if style.italic {
+            write!(self.inner, "{ITALIC}")?;
+        }
+        if style.underline {
+            write!(self.inner, "{UNDERLINE}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: io::Write> Renderer for IrcRenderer<W> {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        self.style = Some(*style);
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        if self.open {
+            write!(self.inner, "{RESET}")?;
+        }
+        self.open = false;
+        self.style = None;
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.open = false;
+                writeln!(self.inner)?;
+            }
+            let line = line.replace(FORMATTING_CODES, "");
+            if line.is_empty() {
+                continue;
+            }
+            self.open(&line)?;
+            write!(self.inner, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The number of the IRC color closest to the given color.
+fn irc(color: termcolor::Color) -> u8 {
+    let (r, g, b) = to_rgb(color);
+    let distance = |&(pr, pg, pb): &(u8, u8, u8)| {
+        let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+        d(r, pr) + d(g, pg) + d(b, pb)
+    };
+    (0..PALETTE.len())
+        .min_by_key(|&i| distance(&PALETTE[i]))
+        .expect("the palette is not empty") as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Highlighter;
+
+    #[test]
+    fn test_highlight_irc() {
+        let output = Highlighter::default().highlight_irc("= A\n*b\nc*").unwrap();
+        assert_eq!(output, "\x0311\x02=\x0F A\n\x0308\x02*\x0Fb\nc*");
+
+        let output = Highlighter::default()
+            .with_soft_limit(10)
+            .highlight_irc("= A\n*b\nc*")
+            .unwrap();
+        assert_eq!(output, "\x0311=\x0F A\n*b\nc*");
+
+        let output = Highlighter::default()
+            .highlight_irc("a\x034,5b\x02 *c\x0F*")
+            .unwrap();
+        assert_eq!(output, "a4,5b \x0308\x02*\x0Fc*");
+    }
+}
@@ -0,0 +1,271 @@
+//! Writing styled spans as JSON, for consumers not written in Rust.
+use std::io;
+
+use serde::Serialize;
+use typst_syntax::SyntaxKind;
+
+use crate::{Error, Style, StyledSpan, Theme};
+
+/// The version of the JSON output written by [`Highlighter::highlight_json`].
+///
+/// It is increased whenever the output changes in a way that could break consumers.
+///
+/// [`Highlighter::highlight_json`]: crate::Highlighter::highlight_json
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct Document<'a> {
+    version: u32,
+    spans: Vec<Record<'a>>,
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    start: usize,
+    end: usize,
+    line: usize,
+    column: usize,
+    kind: Option<&'static str>,
+    tag: Option<&'static str>,
+    style: &'a Style,
+}
+
+/// Write the spans as a JSON document.
+pub(crate) fn write_json<W: io::Write>(spans: &[StyledSpan], out: W) -> Result<(), Error> {
+    let (mut line, mut column) = (1, 1);
+    let spans = spans
+        .iter()
+        .map(|span| {
+            let record = Record {
+                start: span.range.start,
+                end: span.range.end,
+                line,
+                column,
+                kind: span.kind.map(kind_name),
+                tag: span.tag.map(Theme::tag_name),
+                style: &span.style,
+            };
+            for c in span.text.chars() {
+                if c == '\n' {
+                    line += 1;
+                    column = 1;
+                } else {
+                    column += 1;
+                }
+            }
+            record
+        })
+        .collect();
+
+    let document = Document {
+        version: JSON_SCHEMA_VERSION,
+        spans,
+    };
+    serde_json::to_writer(out, &document)?;
+    Ok(())
+}
+
+/// The name of a syntax kind in kebab case, e.g. `raw-lang`.
+///
+/// The names are part of the output format, so they are spelled out instead of derived
+/// from the `Debug` output, which may change with any release of `typst-syntax`.
+fn kind_name(kind: SyntaxKind) -> &'static str {
+    match kind {
+        SyntaxKind::End => "end",
+        SyntaxKind::Error => "error",
+        SyntaxKind::LineComment => "line-comment",
+        SyntaxKind::BlockComment => "block-comment",
+        SyntaxKind::Markup => "markup",
+        SyntaxKind::Text => "text",
+        SyntaxKind::Space => "space",
+        SyntaxKind::Linebreak => "linebreak",
+        SyntaxKind::Parbreak => "parbreak",
+        SyntaxKind::Escape => "escape",
+        SyntaxKind::Shorthand => "shorthand",
+        SyntaxKind::SmartQuote => "smart-quote",
+        SyntaxKind::Strong => "strong",
+        SyntaxKind::Emph => "emph",
+        SyntaxKind::Raw => "raw",
+        SyntaxKind::RawLang => "raw-lang",
+        SyntaxKind::RawDelim => "raw-delim",
+        SyntaxKind::RawTrimmed => "raw-trimmed",
+        SyntaxKind::Link => "link",
+        SyntaxKind::Label => "label",
+        SyntaxKind::Ref => "ref",
+        SyntaxKind::RefMarker => "ref-marker",
+        SyntaxKind::Heading => "heading",
+        SyntaxKind::HeadingMarker => "heading-marker",
+        SyntaxKind::ListItem => "list-item",
+        SyntaxKind::ListMarker => "list-marker",
+        SyntaxKind::EnumItem => "enum-item",
+        SyntaxKind::EnumMarker => "enum-marker",
+        SyntaxKind::TermItem => "term-item",
+        SyntaxKind::TermMarker => "term-marker",
+        SyntaxKind::Equation => "equation",
+        SyntaxKind::Math => "math",
+        SyntaxKind::MathIdent => "math-ident",
+        SyntaxKind::MathShorthand => "math-shorthand",
+        SyntaxKind::MathAlignPoint => "math-align-point",
+        SyntaxKind::MathDelimited => "math-delimited",
+        SyntaxKind::MathAttach => "math-attach",
+        SyntaxKind::MathPrimes => "math-primes",
+        SyntaxKind::MathFrac => "math-frac",
+        SyntaxKind::MathRoot => "math-root",
+        SyntaxKind::Hash => "hash",
+        SyntaxKind::LeftBrace => "left-brace",
+        SyntaxKind::RightBrace => "right-brace",
+        SyntaxKind::LeftBracket => "left-bracket",
+        SyntaxKind::RightBracket => "right-bracket",
+        SyntaxKind::LeftParen => "left-paren",
+        SyntaxKind::RightParen => "right-paren",
+        SyntaxKind::Comma => "comma",
+        SyntaxKind::Semicolon => "semicolon",
+        SyntaxKind::Colon => "colon",
+        SyntaxKind::Star => "star",
+        SyntaxKind::Underscore => "underscore",
+        SyntaxKind::Dollar => "dollar",
+        SyntaxKind::Plus => "plus",
+        SyntaxKind::Minus => "minus",
+        SyntaxKind::Slash => "slash",
+        SyntaxKind::Hat => "hat",
+        SyntaxKind::Prime => "prime",
+        SyntaxKind::Dot => "dot",
+        SyntaxKind::Eq => "eq",
+        SyntaxKind::EqEq => "eq-eq",
+        SyntaxKind::ExclEq => "excl-eq",
+        SyntaxKind::Lt => "lt",
+        SyntaxKind::LtEq => "lt-eq",
+        SyntaxKind::Gt => "gt",
+        SyntaxKind::GtEq => "gt-eq",
+        SyntaxKind::PlusEq => "plus-eq",
+        SyntaxKind::HyphEq => "hyph-eq",
+        SyntaxKind::StarEq => "star-eq",
+        SyntaxKind::SlashEq => "slash-eq",
+        SyntaxKind::Dots => "dots",
+        SyntaxKind::Arrow => "arrow",
+        SyntaxKind::Root => "root",
+        SyntaxKind::Not => "not",
+        SyntaxKind::And => "and",
+        SyntaxKind::Or => "or",
+        SyntaxKind::None => "none",
+        SyntaxKind::Auto => "auto",
+        SyntaxKind::Let => "let",
+        SyntaxKind::Set => "set",
+        SyntaxKind::Show => "show",
+        SyntaxKind::Context => "context",
+        SyntaxKind::If => "if",
+        SyntaxKind::Else => "else",
+        SyntaxKind::For => "for",
+        SyntaxKind::In => "in",
+        SyntaxKind::While => "while",
+        SyntaxKind::Break => "break",
+        SyntaxKind::Continue => "continue",
+        SyntaxKind::Return => "return",
+        SyntaxKind::Import => "import",
+        SyntaxKind::Include => "include",
+        SyntaxKind::As => "as",
+        SyntaxKind::Code => "code",
+        SyntaxKind::Ident => "ident",
+        SyntaxKind::Bool => "bool",
+        SyntaxKind::Int => "int",
+        SyntaxKind::Float => "float",
+        SyntaxKind::Numeric => "numeric",
+        SyntaxKind::Str => "str",
+        SyntaxKind::CodeBlock => "code-block",
+        SyntaxKind::ContentBlock => "content-block",
+        SyntaxKind::Parenthesized => "parenthesized",
+        SyntaxKind::Array => "array",
+        SyntaxKind::Dict => "dict",
+        SyntaxKind::Named => "named",
+        SyntaxKind::Keyed => "keyed",
+        SyntaxKind::Unary => "unary",
+        SyntaxKind::Binary => "binary",
+        SyntaxKind::FieldAccess => "field-access",
+        SyntaxKind::FuncCall => "func-call",
+        SyntaxKind::Args => "args",
+        SyntaxKind::Spread => "spread",
+        SyntaxKind::Closure => "closure",
+        SyntaxKind::Params => "params",
+        SyntaxKind::LetBinding => "let-binding",
+        SyntaxKind::SetRule => "set-rule",
+        SyntaxKind::ShowRule => "show-rule",
+        SyntaxKind::Contextual => "contextual",
+        SyntaxKind::Conditional => "conditional",
+        SyntaxKind::WhileLoop => "while-loop",
+        SyntaxKind::ForLoop => "for-loop",
+        SyntaxKind::ModuleImport => "module-import",
+        SyntaxKind::ImportItems => "import-items",
+        SyntaxKind::ImportItemPath => "import-item-path",
+        SyntaxKind::RenamedImportItem => "renamed-import-item",
+        SyntaxKind::ModuleInclude => "module-include",
+        SyntaxKind::LoopBreak => "loop-break",
+        SyntaxKind::LoopContinue => "loop-continue",
+        SyntaxKind::FuncReturn => "func-return",
+        SyntaxKind::Destructuring => "destructuring",
+        SyntaxKind::DestructAssignment => "destruct-assignment",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use typst_syntax::SyntaxKind;
+
+    use super::kind_name;
+    use crate::{Highlighter, JSON_SCHEMA_VERSION};
+
+    #[test]
+    fn test_kind_names() {
+        assert_eq!(kind_name(SyntaxKind::Raw), "raw");
+        assert_eq!(kind_name(SyntaxKind::RawLang), "raw-lang");
+        assert_eq!(kind_name(SyntaxKind::HeadingMarker), "heading-marker");
+        assert_eq!(kind_name(SyntaxKind::MathIdent), "math-ident");
+        assert_eq!(kind_name(SyntaxKind::EqEq), "eq-eq");
+        assert_eq!(kind_name(SyntaxKind::FuncCall), "func-call");
+        assert_eq!(
+            kind_name(SyntaxKind::DestructAssignment),
+            "destruct-assignment"
+        );
+    }
+
+    #[test]
+    fn test_highlight_json() {
+        let input = "= Hi\n```rs\nfn a() {}\n```";
+        let output = Highlighter::default().highlight_json(input).unwrap();
+        let output: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(output["version"], JSON_SCHEMA_VERSION);
+
+        let spans = output["spans"].as_array().unwrap();
+        assert_eq!(
+            spans[0],
+            json!({
+                "start": 0,
+                "end": 1,
+                "line": 1,
+                "column": 1,
+                "kind": "heading-marker",
+                "tag": "heading",
+                "style": { "fg": "cyan", "bold": true },
+            })
+        );
+        assert_eq!(
+            spans[4],
+            json!({
+                "start": 5,
+                "end": 8,
+                "line": 2,
+                "column": 1,
+                "kind": "raw-delim",
+                "tag": "raw",
+                "style": { "fg": "white" },
+            })
+        );
+
+        let keyword = spans.iter().find(|span| span["start"] == 11).unwrap();
+        assert_eq!(keyword["line"], 3);
+        assert_eq!(keyword["kind"], Value::Null);
+        assert_eq!(keyword["tag"], Value::Null);
+        assert_eq!(keyword["style"], json!({ "fg": "magenta" }));
+    }
+}
@@ -0,0 +1,178 @@
+//! Rendering highlighted code as LaTeX using `xcolor` and `fancyvrb`.
+use std::{fmt::Write as _, io};
+
+use typst_syntax::Tag;
+
+use crate::{
+    color::{hex, HexFormat},
+    render::Renderer,
+    Style, Theme,
+};
+
+/// Renders styles as `\textcolor`, `\textbf` and similar commands.
+///
+/// The output is meant to be placed inside of a `Verbatim` environment of `fancyvrb`
+/// with `commandchars=\\\{\}`, in which only backslashes and braces need to be escaped.
+/// Typst tokens use the colors defined by [`Highlighter::latex_colors`].
+///
+/// [`Highlighter::latex_colors`]: crate::Highlighter::latex_colors
+pub struct LatexRenderer<W> {
+    inner: W,
+    style: Option<(Style, Option<Tag>)>,
+    open: bool,
+}
+
+impl<W: io::Write> LatexRenderer<W> {
+    /// Create a renderer writing to the given writer.
+    pub fn new(writer: W) -> LatexRenderer<W> {
+        LatexRenderer {
+            inner: writer,
+            style: None,
+            open: false,
+        }
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        let Some((style, tag)) = self.style.filter(|_| !self.open) else {
+            return Ok(());
+        };
+        self.open = true;
+        let name = tag.map(Theme::tag_name);
+        // Mixing the color with white makes it lighter on paper.
+        let dim = if style.dimmed { "!60" } else { "" };
+        match (style.fg, name) {
+            (Some(_), Some(name)) => write!(self.inner, "\\textcolor{{typ-{name}{dim}}}{{")?,
+            (Some(fg), None) => write!(
+                self.inner,
+                "\\textcolor[HTML]{{{}}}{{",
+                hex(fg, HexFormat::Latex)
+            )?,
+            (None, _) if style.dimmed => write!(self.inner, "\\textcolor{{gray}}{{")?,
+            (None, _) => {}
+        }
+        if let Some(bg) = style.bg {
+            match name {
+                Some(name) => write!(self.inner, "\\colorbox{{typ-{name}-bg}}{{")?,
+                None => write!(
+                    self.inner,
+                    "\\colorbox[HTML]{{{}}}{{",
+                    hex(bg, HexFormat::Latex)
+                )?,
+            }
+        }
+        if style.bold {
+            write!(self.inner, "\\textbf{{")?;
+        }
+        if style.italic {
+            write!(self.inner, "\\textit{{")?;
+        }
+        if style.underline {
+            write!(self.inner, "\\underline{{")?;
+        }
+        Ok(())
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        let Some((style, _)) = self.style.filter(|_| self.open) else {
+            return Ok(());
+        };
+        self.open = false;
+        let count = usize::from(style.fg.is_some() || style.dimmed)
+            + usize::from(style.bg.is_some())
+            + usize::from(style.bold)
+            + usize::from(style.italic)
+            + usize::from(style.underline);
+        write!(self.inner, "{}", "}".repeat(count))
+    }
+}
+
+impl<W: io::Write> Renderer for LatexRenderer<W> {
+    fn start_style(&mut self, style: &Style, tag: Option<Tag>) -> io::Result<()> {
+        self.style = Some((*style, tag));
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        self.close()?;
+        self.style = None;
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        // Commands can't span multiple lines in a `Verbatim` environment,
+        // so they are closed at the end of each line and opened again.
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.close()?;
+                writeln!(self.inner)?;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            self.open()?;
+            let mut rest = line;
+            while let Some(index) = rest.find(['\\', '{', '}']) {
+                let escaped = match rest.as_bytes()[index] {
+                    b'\\' => "\\textbackslash{}",
+                    b'{' => "\\{",
+                    _ => "\\}",
+                };
+                write!(self.inner, "{}{escaped}", &rest[..index])?;
+                rest = &rest[index + 1..];
+            }
+            write!(self.inner, "{rest}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Generate `\definecolor` commands for the colors of a theme.
+pub(crate) fn colors(theme: &Theme) -> String {
+    let mut definitions = String::new();
+    for &tag in Tag::LIST {
+        let style = theme.get(tag);
+        let name = Theme::tag_name(tag);
+        if let Some(fg) = style.fg {
+            writeln!(
+                definitions,
+                "\\definecolor{{typ-{name}}}{{HTML}}{{{}}}",
+                hex(fg, HexFormat::Latex)
+            )
+            .unwrap();
+        }
+        if let Some(bg) = style.bg {
+            writeln!(
+                definitions,
+                "\\definecolor{{typ-{name}-bg}}{{HTML}}{{{}}}",
+                hex(bg, HexFormat::Latex)
+            )
+            .unwrap();
+        }
+    }
+    definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Highlighter;
+
+    #[test]
+    fn test_highlight_latex() {
+        let output = Highlighter::default()
+            .highlight_latex("#\"a\\b{}\"\n*x\ny*")
+            .unwrap();
+        assert!(output.contains("\\definecolor{typ-string}{HTML}{00CD00}\n"));
+        assert!(output.contains(
+            "\\begin{Verbatim}[commandchars=\\\\\\{\\}]\n\
+             \\textcolor{typ-string}{#\"a\\textbackslash{}b\\{\\}\"}\n\
+             \\textcolor{typ-strong}{\\textbf{*}}x\n\
+             y*\n\
+             \\end{Verbatim}\n"
+        ));
+    }
+}
@@ -7,22 +7,82 @@
 //!     .with_soft_limit(2000)
 //!     .highlight("This is _Typst_ #underline[code].");
 //! ```
-use std::{io::Write, sync::LazyLock};
+use std::{
+    collections::HashMap,
+    fmt,
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, LazyLock},
+};
 
 use syntect::{
-    easy::HighlightLines, highlighting::FontStyle, parsing::SyntaxSet, util::LinesWithEndings,
+    easy::HighlightLines,
+    highlighting::FontStyle,
+    parsing::{SyntaxReference, SyntaxSet},
+    util::LinesWithEndings,
 };
-use termcolor::{Color, ColorSpec, WriteColor};
-use two_face::theme::{EmbeddedLazyThemeSet, EmbeddedThemeName};
+use termcolor::{Color, WriteColor};
+use two_face::theme::EmbeddedLazyThemeSet;
 use typst_syntax::{
     ast::{self, AstNode},
-    LinkedNode, Tag,
+    LinkedNode, SyntaxKind, SyntaxNode, Tag,
 };
 
+mod bbcode;
+mod color;
+mod guess;
+mod html;
+mod irc;
+mod json;
+mod latex;
+#[cfg(any(feature = "reedline", feature = "rustyline"))]
+mod line_editor;
+mod matrix;
+mod pango;
+mod raw;
+mod render;
+mod rtf;
+mod svg;
+mod theme;
+#[cfg(feature = "ratatui")]
+mod tui;
+mod typst;
+mod unicode;
+
+pub use bbcode::BbcodeRenderer;
+pub use color::ColorDepth;
+pub use html::{HtmlRenderer, HtmlStyle};
+pub use irc::IrcRenderer;
+pub use json::JSON_SCHEMA_VERSION;
+pub use latex::LatexRenderer;
+#[cfg(feature = "reedline")]
+pub use line_editor::ReedlineHighlighter;
+#[cfg(feature = "rustyline")]
+pub use line_editor::RustylineHighlighter;
+pub use matrix::MatrixRenderer;
+pub use pango::PangoRenderer;
+pub use raw::{load_syntaxes, RawTheme};
+pub use render::{AnsiRenderer, Renderer, StyledSpan};
+pub use rtf::RtfRenderer;
+pub use svg::{SvgOptions, SvgRenderer};
+pub use theme::{parse_color, Style, Theme};
+#[cfg(feature = "ratatui")]
+pub use tui::RatatuiRenderer;
+pub use typst::TypstRenderer;
+
+use render::{Discard, Emitter};
+
 /// Module with external dependencies exposed by this library.
 pub mod ext {
+    #[cfg(feature = "ratatui")]
+    pub use ratatui;
+    #[cfg(feature = "reedline")]
+    pub use reedline;
+    #[cfg(feature = "rustyline")]
+    pub use rustyline;
     pub use syntect;
     pub use termcolor;
+    pub use two_face;
     pub use typst_syntax;
 }
 
@@ -36,6 +96,35 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error(transparent)]
     Syntect(#[from] syntect::Error),
+    #[error(transparent)]
+    SyntectLoading(#[from] syntect::LoadingError),
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("unknown theme format of `{}`, expected a `.toml` or `.json` file", .0.display())]
+    UnknownThemeFormat(PathBuf),
+    #[error("`{0}` is neither a style attribute nor a color")]
+    InvalidStyle(String),
+}
+
+/// A problem that does not prevent highlighting, but likely makes the output worse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Warning {
+    /// No syntax was found for the language tag of a raw block,
+    /// so its content is not highlighted.
+    UnknownLanguage(String),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::UnknownLanguage(lang) => {
+                write!(f, "no syntax found for raw block language `{lang}`")
+            }
+        }
+    }
 }
 
 /// The kind of input syntax.
@@ -46,11 +135,17 @@ pub enum SyntaxMode {
     Math,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Highlighter {
     discord: bool,
     syntax_mode: SyntaxMode,
     soft_limit: Option<usize>,
+    theme: Theme,
+    color_depth: ColorDepth,
+    raw_theme: RawTheme,
+    syntax_set: Option<Arc<SyntaxSet>>,
+    lang_aliases: Arc<HashMap<String, String>>,
+    lang_guessing: Option<f32>,
 }
 
 impl Default for Highlighter {
@@ -59,6 +154,12 @@ impl Default for Highlighter {
             discord: false,
             syntax_mode: SyntaxMode::Markup,
             soft_limit: None,
+            theme: Theme::DEFAULT,
+            color_depth: ColorDepth::Ansi256,
+            raw_theme: RawTheme::default(),
+            syntax_set: None,
+            lang_aliases: Arc::new(raw::default_lang_aliases()),
+            lang_guessing: None,
         }
     }
 }
@@ -70,6 +171,9 @@ impl Highlighter {
     /// Additionally, any code blocks will be escaped.
     /// The output might not look like the input.
     ///
+    /// Only ANSI output is meant for Discord, so other output formats ignore this,
+    /// except that [`Highlighter::highlight_typst`] uses the same colors.
+    ///
     /// Default: `false`.
     pub fn for_discord(&mut self) -> &mut Self {
         self.discord = true;
@@ -89,11 +193,113 @@ impl Highlighter {
     /// This means that if the size limit is exceeded, less colors are used
     /// in order to get below that size limit.
     /// If it is not possible to get below that limit, the text is printed anyway.
+    ///
+    /// The limit applies to the whole output of each format.
+    /// It is ignored by [`Highlighter::spans`] and the JSON output, which always contain
+    /// every span, and by [`Highlighter::highlight_with`], which can't measure the output.
     pub fn with_soft_limit(&mut self, soft_limit: usize) -> &mut Self {
         self.soft_limit = Some(soft_limit);
         self
     }
 
+    /// Use the given theme to determine the style of each token.
+    ///
+    /// Default: [`Theme::DEFAULT`].
+    pub fn with_theme(&mut self, theme: Theme) -> &mut Self {
+        self.theme = theme;
+        self
+    }
+
+    /// How many colors the output may use.
+    ///
+    /// Colors of the theme and of raw blocks that can't be displayed
+    /// are converted to the closest color that can.
+    /// Output for Discord never uses more than [`ColorDepth::Ansi8`].
+    ///
+    /// Default: [`ColorDepth::Ansi256`].
+    pub fn with_color_depth(&mut self, color_depth: ColorDepth) -> &mut Self {
+        self.color_depth = color_depth;
+        self
+    }
+
+    /// Use the given syntect theme for raw blocks with a language tag.
+    ///
+    /// Default: [`EmbeddedThemeName::Base16`], which only uses the terminal's palette.
+    ///
+    /// [`EmbeddedThemeName::Base16`]: two_face::theme::EmbeddedThemeName::Base16
+    pub fn with_raw_theme(&mut self, theme: impl Into<RawTheme>) -> &mut Self {
+        self.raw_theme = theme.into();
+        self
+    }
+
+    /// Use the given syntaxes to highlight raw blocks with a language tag,
+    /// in addition to [`two_face::syntax::extra_newlines`].
+    ///
+    /// They take precedence over default syntaxes with the same name or file extension.
+    /// The syntaxes must be built with newlines, see [`load_syntaxes`].
+    ///
+    /// Default: `None`.
+    pub fn with_syntax_set(&mut self, syntax_set: impl Into<Arc<SyntaxSet>>) -> &mut Self {
+        self.syntax_set = Some(syntax_set.into());
+        self
+    }
+
+    /// Highlight raw blocks tagged with `alias` using the syntax for `lang`.
+    ///
+    /// Language tags are matched case-insensitively, and `lang` may be anything
+    /// that identifies a syntax by name or file extension, such as `rust` or `rs`.
+    /// Common aliases like `console` or `jsonc` are defined by default.
+    pub fn with_lang_alias(&mut self, alias: &str, lang: &str) -> &mut Self {
+        Arc::make_mut(&mut self.lang_aliases).insert(alias.to_lowercase(), lang.to_owned());
+        self
+    }
+
+    /// Guess the language of raw blocks without a language tag.
+    ///
+    /// The language is guessed from shebangs, other characteristic first lines,
+    /// and typical content of a few common languages.
+    /// A raw block is only highlighted if the guess has at least the given confidence,
+    /// which ranges from `0.0` to `1.0`.
+    /// Inline raw text is never guessed, as it is usually too short to be recognized.
+    ///
+    /// Default: `None`, which disables guessing.
+    pub fn with_lang_guessing(&mut self, min_confidence: Option<f32>) -> &mut Self {
+        self.lang_guessing = min_confidence;
+        self
+    }
+
+    /// Check Typst code for problems that don't prevent highlighting it.
+    ///
+    /// This reports raw blocks whose language tag doesn't match any syntax,
+    /// each language only once.
+    pub fn warnings(&self, input: &str) -> Vec<Warning> {
+        fn collect(highlighter: &Highlighter, node: &SyntaxNode, warnings: &mut Vec<Warning>) {
+            if let Some(raw) = node.cast::<ast::Raw>() {
+                let lang = raw.lang().map(|lang| lang.get().as_str());
+                if let Some(mode) = lang.and_then(|lang| highlighter.typst_mode(lang)) {
+                    let code: String = raw
+                        .lines()
+                        .map(|line| line.get().as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    collect(highlighter, &parse(&code, mode), warnings);
+                } else if let Some(lang) = lang {
+                    let warning = Warning::UnknownLanguage(lang.to_string());
+                    if highlighter.find_syntax(lang).is_none() && !warnings.contains(&warning) {
+                        warnings.push(warning);
+                    }
+                }
+            }
+            for child in node.children() {
+                collect(highlighter, child, warnings);
+            }
+        }
+
+        let mut warnings = Vec::new();
+        collect(self, &self.parse(input), &mut warnings);
+        warnings
+    }
+
     /// Highlight Typst code and return the highlighted string.
     pub fn highlight(&self, input: &str) -> Result<String, Error> {
         let mut out = termcolor::Ansi::new(Vec::new());
@@ -103,15 +309,354 @@ impl Highlighter {
 
     /// Highlight Typst code and write it to the given output.
     pub fn highlight_to<W: WriteColor>(&self, input: &str, out: W) -> Result<(), Error> {
-        let parsed = match self.syntax_mode {
-            SyntaxMode::Code => typst_syntax::parse_code(input),
-            SyntaxMode::Markup => typst_syntax::parse(input),
-            SyntaxMode::Math => typst_syntax::parse_math(input),
-        };
+        let parsed = self.parse(input);
         let linked = typst_syntax::LinkedNode::new(&parsed);
         self.highlight_node_to(&linked, out)
     }
 
+    /// Highlight Typst code using the given renderer and return the renderer.
+    ///
+    /// Use this to produce output in formats not supported by this library.
+    /// The soft limit is not applied, as the size of the output is unknown.
+    pub fn highlight_with<R: Renderer>(&self, input: &str, renderer: R) -> Result<R, Error> {
+        let parsed = self.parse(input);
+        let linked = LinkedNode::new(&parsed);
+        self.highlight_node_with(&linked, renderer)
+    }
+
+    /// Highlight a linked syntax node using the given renderer and return the renderer.
+    ///
+    /// See [`Highlighter::highlight_node_to`] on how to obtain a [`LinkedNode`].
+    pub fn highlight_node_with<R: Renderer>(
+        &self,
+        node: &LinkedNode,
+        renderer: R,
+    ) -> Result<R, Error> {
+        self.render(node, renderer, HighlightLevel::All)
+    }
+
+    /// Classify and style Typst code without rendering it.
+    ///
+    /// Returns the pieces the input consists of in order, including the spans
+    /// syntect produces inside raw blocks.
+    /// Neighbouring pieces may have the same style.
+    /// Output for Discord is not supported, so [`Highlighter::for_discord`] is ignored.
+    pub fn spans(&self, input: &str) -> Result<Vec<StyledSpan>, Error> {
+        let parsed = self.parse(input);
+        let node = LinkedNode::new(&parsed);
+        let highlighter = Highlighter {
+            discord: false,
+            ..self.clone()
+        };
+        let mut out = Emitter::new(Discard);
+        out.record_spans();
+        highlighter.highlight_node(HighlightLevel::All, &node, &mut out)?;
+        Ok(out.take_spans())
+    }
+
+    /// Classify and style Typst code and return the spans as JSON.
+    ///
+    /// The output is an object with the [`JSON_SCHEMA_VERSION`] as `version`
+    /// and the [`Highlighter::spans`] as `spans`.
+    /// Each span is an object with these fields:
+    ///
+    /// - `start` and `end`: The byte range of the span in the input.
+    /// - `line` and `column`: The position of the start of the span,
+    ///   both starting at 1, with the column counted in Unicode scalar values.
+    /// - `kind`: The kind of syntax node in kebab case, e.g. `raw-lang`,
+    ///   or `null` for text highlighted by syntect.
+    /// - `tag`: The name of the highlighting tag as used in theme files, e.g. `keyword`,
+    ///   or `null`.
+    /// - `style`: The style as used in theme files, e.g. `{"fg": "red", "bold": true}`.
+    pub fn highlight_json(&self, input: &str) -> Result<String, Error> {
+        collect_string(|out| self.highlight_json_to(input, out))
+    }
+
+    /// Classify and style Typst code and write the spans as JSON to the given output.
+    ///
+    /// See [`Highlighter::highlight_json`] for details.
+    pub fn highlight_json_to<W: Write>(&self, input: &str, out: W) -> Result<(), Error> {
+        json::write_json(&self.spans(input)?, out)
+    }
+
+    /// Highlight Typst code as HTML and return the highlighted string.
+    ///
+    /// The code is wrapped in `<pre><code>` and each styled token in a `<span>`,
+    /// which is styled as given, see [`HtmlRenderer`].
+    pub fn highlight_html(&self, input: &str, html_style: HtmlStyle) -> Result<String, Error> {
+        collect_string(|out| self.highlight_html_to(input, html_style, out))
+    }
+
+    /// Highlight Typst code as HTML and write it to the given output.
+    ///
+    /// See [`Highlighter::highlight_html`] for details.
+    pub fn highlight_html_to<W: Write>(
+        &self,
+        input: &str,
+        html_style: HtmlStyle,
+        out: W,
+    ) -> Result<(), Error> {
+        self.render_to(input, out, |out, pass| {
+            write!(out, "<pre><code>")?;
+            pass.render(HtmlRenderer::new(&mut *out, html_style))?;
+            write!(out, "</code></pre>")?;
+            Ok(())
+        })
+    }
+
+    /// Highlight Typst code as the content of a Matrix message and return it as JSON.
+    ///
+    /// The `formatted_body` wraps the code in `<pre><code>` and only uses HTML
+    /// allowed by the Matrix specification, see [`MatrixRenderer`].
+    /// The `body` contains the input as a Markdown code block for clients without HTML support.
+    /// The soft limit is applied to the whole JSON object.
+    pub fn highlight_matrix(&self, input: &str) -> Result<String, Error> {
+        collect_string(|out| self.highlight_matrix_to(input, out))
+    }
+
+    /// Highlight Typst code as the content of a Matrix message and write it as JSON to the given output.
+    ///
+    /// See [`Highlighter::highlight_matrix`] for details.
+    pub fn highlight_matrix_to<W: Write>(&self, input: &str, out: W) -> Result<(), Error> {
+        self.render_to(input, out, |out, pass| {
+            let mut formatted_body = b"<pre><code>".to_vec();
+            pass.render(MatrixRenderer::new(&mut formatted_body))?;
+            formatted_body.extend_from_slice(b"</code></pre>");
+            let formatted_body =
+                String::from_utf8(formatted_body).expect("the output should be entirely UTF-8");
+            serde_json::to_writer(out, &matrix::Message::new(input, &formatted_body))?;
+            Ok(())
+        })
+    }
+
+    /// Highlight Typst code as BBCode and return the highlighted string.
+    ///
+    /// The code is wrapped in the given tag, e.g. `font=monospace`,
+    /// see [`BbcodeRenderer`] for the tags used inside.
+    /// The forum has to read those tags inside of the wrapper, which many forums don't do
+    /// inside of `[code]`, so `code` is usually not suitable.
+    /// `None` leaves the code unwrapped.
+    pub fn highlight_bbcode(&self, input: &str, wrapper: Option<&str>) -> Result<String, Error> {
+        collect_string(|out| self.highlight_bbcode_to(input, wrapper, out))
+    }
+
+    /// Highlight Typst code as BBCode and write it to the given output.
+    ///
+    /// See [`Highlighter::highlight_bbcode`] for details.
+    pub fn highlight_bbcode_to<W: Write>(
+        &self,
+        input: &str,
+        wrapper: Option<&str>,
+        out: W,
+    ) -> Result<(), Error> {
+        let (open, close) = wrapper.map(bbcode::wrapper_tags).unwrap_or_default();
+        self.render_to(input, out, |out, pass| {
+            write!(out, "{open}")?;
+            pass.render(BbcodeRenderer::new(&mut *out))?;
+            write!(out, "{close}")?;
+            Ok(())
+        })
+    }
+
+    /// Highlight Typst code using mIRC formatting codes and return the highlighted string.
+    ///
+    /// Colors are mapped onto the 16 colors of IRC, see [`IrcRenderer`].
+    pub fn highlight_irc(&self, input: &str) -> Result<String, Error> {
+        collect_string(|out| self.highlight_irc_to(input, out))
+    }
+
+    /// Highlight Typst code using mIRC formatting codes and write it to the given output.
+    ///
+    /// See [`Highlighter::highlight_irc`] for details.
+    pub fn highlight_irc_to<W: Write>(&self, input: &str, out: W) -> Result<(), Error> {
+        self.render_to(input, out, |out, pass| {
+            pass.render(IrcRenderer::new(out))?;
+            Ok(())
+        })
+    }
+
+    /// Highlight Typst code as Pango markup and return the highlighted string.
+    ///
+    /// The code is wrapped in `<tt>` and each styled token in a `<span>`,
+    /// which can be shown by GTK labels and many notification daemons.
+    pub fn highlight_pango(&self, input: &str) -> Result<String, Error> {
+        collect_string(|out| self.highlight_pango_to(input, out))
+    }
+
+    /// Highlight Typst code as Pango markup and write it to the given output.
+    ///
+    /// See [`Highlighter::highlight_pango`] for details.
+    pub fn highlight_pango_to<W: Write>(&self, input: &str, out: W) -> Result<(), Error> {
+        self.render_to(input, out, |out, pass| {
+            write!(out, "<tt>")?;
+            pass.render(PangoRenderer::new(&mut *out))?;
+            write!(out, "</tt>")?;
+            Ok(())
+        })
+    }
+
+    /// Highlight Typst code as Typst markup and return the highlighted string.
+    ///
+    /// The output is a `#block` in a monospace font which contains the input as escaped
+    /// markup, styled by `#text` calls, so it looks like the ANSI output when compiled.
+    /// Like the ANSI output, colors are converted to the color depth,
+    /// and [`Highlighter::for_discord`] limits them to the eight basic colors
+    /// without wrapping the output in a code block.
+    pub fn highlight_typst(&self, input: &str) -> Result<String, Error> {
+        collect_string(|out| self.highlight_typst_to(input, out))
+    }
+
+    /// Highlight Typst code as Typst markup and write it to the given output.
+    ///
+    /// See [`Highlighter::highlight_typst`] for details.
+    pub fn highlight_typst_to<W: Write>(&self, input: &str, out: W) -> Result<(), Error> {
+        // Discord only renders the eight basic colors.
+        let color_depth = match self.discord {
+            true => self.color_depth.min(ColorDepth::Ansi8),
+            false => self.color_depth,
+        };
+        self.render_to(input, out, |out, pass| {
+            write!(
+                out,
+                "#block(breakable: true)[#set text(font: \"DejaVu Sans Mono\");"
+            )?;
+            pass.render(TypstRenderer::new(&mut *out, color_depth))?;
+            writeln!(out, "]")?;
+            Ok(())
+        })
+    }
+
+    /// Express the emphasis of Typst code with styled Unicode letters and return the result.
+    ///
+    /// This is meant for platforms that don't support any formatting.
+    /// Letters and digits of strong text and headings are replaced by their bold counterparts
+    /// from the Mathematical Alphanumeric Symbols block, those of emphasized text by italic
+    /// ones, and those of raw text by monospace ones.
+    /// Other characters are left unchanged.
+    /// The theme is not used, and the output is no longer valid Typst code.
+    pub fn highlight_unicode(&self, input: &str) -> Result<String, Error> {
+        collect_string(|out| self.highlight_unicode_to(input, out))
+    }
+
+    /// Express the emphasis of Typst code with styled Unicode letters and write it to the given output.
+    ///
+    /// See [`Highlighter::highlight_unicode`] for details.
+    pub fn highlight_unicode_to<W: Write>(&self, input: &str, out: W) -> Result<(), Error> {
+        self.render_to(input, out, |out, pass| {
+            let mut letters = String::new();
+            unicode::styled_letters(
+                pass.node,
+                unicode::Letters::Plain,
+                pass.hl_level,
+                &mut letters,
+            );
+            write!(out, "{letters}")?;
+            Ok(())
+        })
+    }
+
+    /// Highlight Typst code as text for [`ratatui`] widgets.
+    ///
+    /// The styles of the spans are derived from the theme,
+    /// and colors are converted to the color depth like in the ANSI output.
+    /// Output for Discord is not supported, so [`Highlighter::for_discord`] is ignored.
+    ///
+    /// Requires the `ratatui` feature.
+    ///
+    /// [`ratatui`]: ext::ratatui
+    #[cfg(feature = "ratatui")]
+    pub fn highlight_ratatui(&self, input: &str) -> Result<ratatui::text::Text<'static>, Error> {
+        let highlighter = Highlighter {
+            discord: false,
+            ..self.clone()
+        };
+        let renderer = RatatuiRenderer::new(self.color_depth);
+        Ok(highlighter.highlight_with(input, renderer)?.into_text())
+    }
+
+    /// Highlight Typst code as an SVG image and return it.
+    ///
+    /// Each line is a `<text>` element with a `<tspan>` per styled token,
+    /// on a background rectangle sized for a typical monospace font.
+    pub fn highlight_svg(&self, input: &str, options: &SvgOptions) -> Result<String, Error> {
+        collect_string(|out| self.highlight_svg_to(input, options, out))
+    }
+
+    /// Highlight Typst code as an SVG image and write it to the given output.
+    ///
+    /// See [`Highlighter::highlight_svg`] for details.
+    pub fn highlight_svg_to<W: Write>(
+        &self,
+        input: &str,
+        options: &SvgOptions,
+        out: W,
+    ) -> Result<(), Error> {
+        self.render_to(input, out, |out, pass| {
+            pass.render(SvgRenderer::new(options.clone()))?
+                .finish(out)?;
+            Ok(())
+        })
+    }
+
+    /// Highlight Typst code as LaTeX and return the highlighted string.
+    ///
+    /// The output defines the colors of the theme with `xcolor`,
+    /// followed by a `Verbatim` environment of `fancyvrb` containing the code.
+    /// Themes for light backgrounds like [`Theme::LIGHT`] are best suited for paper.
+    pub fn highlight_latex(&self, input: &str) -> Result<String, Error> {
+        collect_string(|out| self.highlight_latex_to(input, out))
+    }
+
+    /// Highlight Typst code as LaTeX and write it to the given output.
+    ///
+    /// See [`Highlighter::highlight_latex`] for details.
+    pub fn highlight_latex_to<W: Write>(&self, input: &str, out: W) -> Result<(), Error> {
+        self.render_to(input, out, |out, pass| {
+            writeln!(out, "% Requires the xcolor and fancyvrb packages.")?;
+            write!(out, "{}", self.latex_colors())?;
+            writeln!(out, "\\begin{{Verbatim}}[commandchars=\\\\\\{{\\}}]")?;
+            pass.render(LatexRenderer::new(&mut *out))?;
+            if !input.is_empty() && !input.ends_with('\n') {
+                writeln!(out)?;
+            }
+            writeln!(out, "\\end{{Verbatim}}")?;
+            Ok(())
+        })
+    }
+
+    /// Highlight Typst code as an RTF document and return it.
+    ///
+    /// The document uses a monospace font and can be pasted into word processors.
+    pub fn highlight_rtf(&self, input: &str) -> Result<String, Error> {
+        collect_string(|out| self.highlight_rtf_to(input, out))
+    }
+
+    /// Highlight Typst code as an RTF document and write it to the given output.
+    ///
+    /// See [`Highlighter::highlight_rtf`] for details.
+    pub fn highlight_rtf_to<W: Write>(&self, input: &str, out: W) -> Result<(), Error> {
+        self.render_to(input, out, |out, pass| {
+            pass.render(RtfRenderer::new(&self.theme))?.finish(out)?;
+            Ok(())
+        })
+    }
+
+    /// Generate `\definecolor` commands for the colors of the theme,
+    /// which are used by the LaTeX output.
+    pub fn latex_colors(&self) -> String {
+        latex::colors(&self.theme)
+    }
+
+    /// Generate a stylesheet for the theme, which styles the HTML output
+    /// when using [`HtmlStyle::Classes`].
+    pub fn html_stylesheet(&self) -> String {
+        html::stylesheet(&self.theme)
+    }
+
+    fn parse(&self, input: &str) -> SyntaxNode {
+        parse(input, self.syntax_mode)
+    }
+
     /// Highlight a linked syntax node and write it to the given output.
     ///
     /// Use [`typst_syntax::parse`] to parse a string into a [`SyntaxNode`], and then
@@ -124,93 +669,143 @@ impl Highlighter {
         node: &LinkedNode,
         mut out: W,
     ) -> Result<(), Error> {
-        fn inner_highlight_node<W: WriteColor>(
-            highlighter: &Highlighter,
-            hl_level: HighlightLevel,
-            node: &LinkedNode,
-            out: &mut DeferredWriter<W>,
-            color: &mut ColorSpec,
-        ) -> Result<(), Error> {
-            let prev_color = color.clone();
-
-            if let Some(tag) = typst_syntax::highlight(node) {
-                out.set_color(&highlighter.tag_to_color(hl_level, tag))?;
-            }
+        // Discord only renders the eight basic colors.
+        let color_depth = match self.discord {
+            true => self.color_depth.min(ColorDepth::Ansi8),
+            false => self.color_depth,
+        };
 
-            if let Some(raw) = ast::Raw::from_untyped(node) {
-                highlighter.highlight_raw(hl_level, out, raw)?;
-            } else if node.text().is_empty() {
-                for child in node.children() {
-                    inner_highlight_node(highlighter, hl_level, &child, out, color)?;
-                }
-            } else {
-                write!(out, "{}", node.text())?;
+        let buf = self.render_within_soft_limit(|buf, hl_level| {
+            let renderer = AnsiRenderer::new(termcolor::Ansi::new(buf), color_depth);
+            self.render(node, renderer, hl_level).map(drop)
+        })?;
+        match buf {
+            Some(buf) => out.write_all(&buf)?,
+            None => {
+                let renderer = AnsiRenderer::new(out, color_depth);
+                self.render(node, renderer, HighlightLevel::All)?;
             }
+        }
 
-            out.set_color(&prev_color)?;
-            *color = prev_color;
+        Ok(())
+    }
 
-            Ok(())
+    /// Highlight Typst code in an output format other than ANSI and write it to the given output.
+    ///
+    /// `write` writes the whole output of one pass, usually by wrapping what a renderer writes.
+    /// The output is never wrapped in a code block for Discord,
+    /// and the soft limit applies to the whole output.
+    fn render_to(
+        &self,
+        input: &str,
+        mut out: impl Write,
+        write: impl Fn(&mut dyn Write, RenderPass<'_>) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let parsed = self.parse(input);
+        let node = LinkedNode::new(&parsed);
+        let highlighter = Highlighter {
+            discord: false,
+            ..self.clone()
+        };
+        let pass = |hl_level| RenderPass {
+            highlighter: &highlighter,
+            node: &node,
+            hl_level,
+        };
+
+        match self.render_within_soft_limit(|buf, hl_level| write(buf, pass(hl_level)))? {
+            Some(buf) => out.write_all(&buf)?,
+            None => write(&mut out, pass(HighlightLevel::All))?,
         }
+        Ok(())
+    }
 
-        fn inner<W: WriteColor>(
-            highlighter: &Highlighter,
-            node: &LinkedNode,
-            out: W,
-            hl_level: HighlightLevel,
-        ) -> Result<(), Error> {
-            let mut out = DeferredWriter::new(out);
-            if highlighter.discord {
-                writeln!(out, "```ansi")?;
+    /// Render into an in-memory buffer with the highest highlight level
+    /// that keeps the output below the soft limit.
+    ///
+    /// Returns `None` if no soft limit is given.
+    fn render_within_soft_limit(
+        &self,
+        render: impl Fn(&mut Vec<u8>, HighlightLevel) -> Result<(), Error>,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let Some(soft_limit) = self.soft_limit else {
+            return Ok(None);
+        };
+
+        // Because a soft limit is given, we highlight everything to an in-memory buffer
+        // and check whether the output length is less than the limit.
+        // If the limit was reached, we lower the highlight level.
+        // If the highlight level was reached, we _always_ return the output without highlighting.
+        let mut buf = Vec::new();
+        let mut level = HighlightLevel::All;
+        loop {
+            render(&mut buf, level)?;
+            if buf.len() < soft_limit || level == HighlightLevel::Off {
+                return Ok(Some(buf));
             }
+            buf.clear();
+            level = level.restrict();
+        }
+    }
 
-            inner_highlight_node(highlighter, hl_level, node, &mut out, &mut ColorSpec::new())?;
+    fn render<R: Renderer>(
+        &self,
+        node: &LinkedNode,
+        renderer: R,
+        hl_level: HighlightLevel,
+    ) -> Result<R, Error> {
+        let mut out = Emitter::new(renderer);
+        if self.discord {
+            out.text("```ansi\n")?;
+        }
 
-            if highlighter.discord {
-                // Make sure that the closing fences are on their own line.
-                let mut last_leaf = node.clone();
-                while let Some(child) = last_leaf.children().last() {
-                    last_leaf = child;
-                }
-                if !last_leaf.text().ends_with('\n') {
-                    writeln!(out)?;
-                }
-                writeln!(out, "```")?;
+        self.highlight_node(hl_level, node, &mut out)?;
+
+        if self.discord {
+            // Make sure that the closing fences are on their own line.
+            let mut last_leaf = node.clone();
+            while let Some(child) = last_leaf.children().last() {
+                last_leaf = child;
             }
-            Ok(())
+            if !last_leaf.text().ends_with('\n') {
+                out.text("\n")?;
+            }
+            out.text("```\n")?;
         }
+        Ok(out.finish()?)
+    }
 
-        if let Some(soft_limit) = self.soft_limit {
-            // Because a soft limit is given, we highlight everything to an in-memory buffer
-            // and check whether the output length is less than the limit.
-            // If the limit was reached, we lower the highlight level.
-            // Otherwise, we write it to the real output.
-            // If the highlight level was reached, we _always_ write the output without highlighting.
-            let mut buf_out = termcolor::Ansi::new(Vec::new());
-            let mut level = HighlightLevel::All;
-            loop {
-                inner(self, node, &mut buf_out, level)?;
-                let mut buf = buf_out.into_inner();
-                if buf.len() < soft_limit || level == HighlightLevel::Off {
-                    out.write_all(&buf)?;
-                    break;
-                } else {
-                    buf.clear();
-                    buf_out = termcolor::Ansi::new(buf);
-                    level = level.restrict();
-                }
+    fn highlight_node<R: Renderer>(
+        &self,
+        hl_level: HighlightLevel,
+        node: &LinkedNode,
+        out: &mut Emitter<R>,
+    ) -> Result<(), Error> {
+        if let Some(tag) = typst_syntax::highlight(node) {
+            out.set_style(self.tag_style(hl_level, tag), Some(tag));
+        }
+
+        if let Some(raw) = ast::Raw::from_untyped(node) {
+            self.highlight_raw(hl_level, out, raw)?;
+        } else if node.kind() == SyntaxKind::Link {
+            out.link(node.text(), node.text())?;
+        } else if node.text().is_empty() {
+            for child in node.children() {
+                self.highlight_node(hl_level, &child, out)?;
             }
         } else {
-            inner(self, node, out, HighlightLevel::All)?;
+            out.node_text(node.kind(), node.text())?;
         }
 
+        out.set_style(Style::new(), None);
+
         Ok(())
     }
 
-    fn highlight_raw<W: WriteColor>(
+    fn highlight_raw<R: Renderer>(
         &self,
         hl_level: HighlightLevel,
-        out: &mut DeferredWriter<W>,
+        out: &mut Emitter<R>,
         raw: ast::Raw<'_>,
     ) -> Result<(), Error> {
         let text = raw.to_untyped().clone().into_text();
@@ -234,117 +829,208 @@ impl Highlighter {
 
         // Write opening fence.
         if self.discord && !is_pure_fence {
-            out.set_color(&self.tag_to_color(hl_level, Tag::Comment))?;
-            write!(out, "/* when copying, remove and retype these --> */")?;
+            out.set_style(self.tag_style(hl_level, Tag::Comment), Some(Tag::Comment));
+            out.text("/* when copying, remove and retype these --> */")?;
         }
-        out.set_color(&self.tag_to_color(hl_level, Tag::Raw))?;
-        write!(out, "{fence}")?;
+        out.set_style(self.tag_style(hl_level, Tag::Raw), Some(Tag::Raw));
+        out.node_text(SyntaxKind::RawDelim, &fence)?;
 
         if include_content {
-            if let Some(lang) = raw.lang() {
-                write!(out, "{}", lang.get())?;
-            }
-
             // Trim starting fences.
             let mut inner = text.trim_start_matches('`');
             // Trim closing fences.
             inner = &inner[..inner.len() - (text.len() - inner.len())];
 
+            if let Some(lang) = raw.lang() {
+                out.node_text(SyntaxKind::RawLang, lang.get())?;
+                inner = &inner[lang.get().len()..]; // Trim language tag.
+            }
+
             if let Some(lang) = raw.lang().filter(|_| hl_level >= HighlightLevel::WithRaw) {
-                let lang = lang.get();
-                inner = &inner[lang.len()..]; // Trim language tag.
-                highlight_lang(inner, lang, out)?;
+                self.highlight_code(hl_level, inner, lang.get(), out)?;
+            } else if let Some(lang) = self.guess_lang(hl_level, raw, inner) {
+                self.highlight_code(hl_level, inner, &lang, out)?;
             } else {
-                write!(out, "{inner}")?;
+                out.node_text(SyntaxKind::Text, inner)?;
             }
         }
 
         // Write closing fence.
-        out.set_color(&self.tag_to_color(hl_level, Tag::Raw))?;
-        write!(out, "{fence}")?;
+        out.set_style(self.tag_style(hl_level, Tag::Raw), Some(Tag::Raw));
+        out.node_text(SyntaxKind::RawDelim, &fence)?;
         if self.discord && !is_pure_fence {
-            out.set_color(&self.tag_to_color(hl_level, Tag::Comment))?;
-            write!(out, "/* <-- when copying, remove and retype these */")?;
+            out.set_style(self.tag_style(hl_level, Tag::Comment), Some(Tag::Comment));
+            out.text("/* <-- when copying, remove and retype these */")?;
         }
 
         Ok(())
     }
 
-    fn tag_to_color(&self, hl_level: HighlightLevel, tag: Tag) -> ColorSpec {
-        let mut color = ColorSpec::default();
-        let l1 = hl_level >= HighlightLevel::L1;
-        let l2 = hl_level >= HighlightLevel::L2;
-        let with_styles = hl_level >= HighlightLevel::WithStyles;
-        match tag {
-            Tag::Comment => {
-                if self.discord {
-                    color.set_fg(Some(Color::Black))
-                } else {
-                    color.set_dimmed(true)
-                }
+    /// Guess the language of a raw block without a language tag, if enabled.
+    fn guess_lang(
+        &self,
+        hl_level: HighlightLevel,
+        raw: ast::Raw<'_>,
+        inner: &str,
+    ) -> Option<String> {
+        let min_confidence = self.lang_guessing?;
+        if hl_level < HighlightLevel::WithRaw || !raw.block() || raw.lang().is_some() {
+            return None;
+        }
+        let (lang, confidence) = guess::guess_lang(inner, &self.syntax_sets())?;
+        (confidence >= min_confidence).then_some(lang)
+    }
+
+    /// Highlight the content of a raw block in the given language.
+    fn highlight_code<R: Renderer>(
+        &self,
+        hl_level: HighlightLevel,
+        inner: &str,
+        lang: &str,
+        out: &mut Emitter<R>,
+    ) -> Result<(), Error> {
+        if let Some(mode) = self.typst_mode(lang) {
+            // Typst code is highlighted like the surrounding document,
+            // including any raw blocks nested inside of it.
+            // Leading whitespace is not part of the code, and math
+            // would treat it as an error.
+            let code = inner.trim_start();
+            out.node_text(SyntaxKind::RawTrimmed, &inner[..inner.len() - code.len()])?;
+            let parsed = parse(code, mode);
+            let linked = LinkedNode::new(&parsed);
+            out.set_style(Style::new(), None);
+            self.highlight_node(hl_level, &linked, out)?;
+        } else {
+            self.highlight_lang(inner, lang, out)?;
+        }
+        Ok(())
+    }
+
+    fn highlight_lang<R: Renderer>(
+        &self,
+        input: &str,
+        lang: &str,
+        out: &mut Emitter<R>,
+    ) -> Result<(), Error> {
+        let Some((syntax_set, syntax)) = self.find_syntax(lang) else {
+            out.node_text(SyntaxKind::Text, input)?;
+            return Ok(());
+        };
+        let mut highlighter = HighlightLines::new(syntax, self.raw_theme.get());
+        for line in LinesWithEndings::from(input) {
+            let ranges = highlighter.highlight_line(line, syntax_set)?;
+            for (styles, text) in ranges {
+                let fg = styles.foreground;
+                let font_style = styles.font_style;
+                let style = Style {
+                    fg: convert_rgb_to_ansi_color(fg.r, fg.g, fg.b, fg.a),
+                    bold: font_style.contains(FontStyle::BOLD),
+                    italic: font_style.contains(FontStyle::ITALIC),
+                    underline: font_style.contains(FontStyle::UNDERLINE),
+                    ..Style::new()
+                };
+
+                out.set_style(style, None);
+                out.text(text)?;
             }
-            Tag::Punctuation if l1 => color.set_fg(None),
-            Tag::Escape => color.set_fg(Some(Color::Cyan)),
-            Tag::Strong if l1 => color.set_fg(Some(Color::Yellow)).set_bold(with_styles),
-            Tag::Emph if l1 => color.set_fg(Some(Color::Yellow)).set_italic(with_styles),
-            Tag::Link if l1 => color.set_fg(Some(Color::Blue)).set_underline(with_styles),
-            Tag::Raw => color.set_fg(Some(Color::White)),
-            Tag::Label => color.set_fg(Some(Color::Blue)).set_underline(with_styles),
-            Tag::Ref => color.set_fg(Some(Color::Blue)).set_underline(with_styles),
-            Tag::Heading => color.set_fg(Some(Color::Cyan)).set_bold(with_styles),
-            Tag::ListMarker => color.set_fg(Some(Color::Cyan)),
-            Tag::ListTerm => color.set_fg(Some(Color::Cyan)),
-            Tag::MathDelimiter if l2 => color.set_fg(Some(Color::Cyan)),
-            Tag::MathOperator => color.set_fg(Some(Color::Cyan)),
-            Tag::Keyword => color.set_fg(Some(Color::Magenta)),
-            Tag::Operator if l2 => color.set_fg(Some(Color::Cyan)),
-            Tag::Number => color.set_fg(Some(Color::Yellow)),
-            Tag::String => color.set_fg(Some(Color::Green)),
-            Tag::Function if l2 => color.set_fg(Some(Color::Blue)).set_italic(with_styles),
-            Tag::Interpolated if l2 => color.set_fg(Some(Color::White)),
-            Tag::Error => color.set_fg(Some(Color::Red)),
-            _ => &mut color,
+        }
+
+        Ok(())
+    }
+
+    /// Find the syntax for a language tag and the syntax set it belongs to.
+    fn find_syntax(&self, lang: &str) -> Option<(&SyntaxSet, &SyntaxReference)> {
+        let lang = self.resolve_lang(lang);
+        self.syntax_sets()
+            .into_iter()
+            .find_map(|syntax_set| Some((syntax_set, syntax_set.find_syntax_by_token(&lang)?)))
+    }
+
+    /// The syntax sets to search in order, with the default syntaxes last.
+    fn syntax_sets(&self) -> Vec<&SyntaxSet> {
+        let mut syntax_sets: Vec<&SyntaxSet> = self.syntax_set.iter().map(|set| &**set).collect();
+        syntax_sets.push(&SYNTAX_SET);
+        syntax_sets
+    }
+
+    /// The syntax mode for raw blocks tagged as Typst markup, code or math.
+    fn typst_mode(&self, lang: &str) -> Option<SyntaxMode> {
+        match self.resolve_lang(lang).as_str() {
+            "typ" => Some(SyntaxMode::Markup),
+            "typc" => Some(SyntaxMode::Code),
+            "typm" => Some(SyntaxMode::Math),
+            _ => None,
+        }
+    }
+
+    /// Lowercase a language tag and apply the language aliases.
+    fn resolve_lang(&self, lang: &str) -> String {
+        let lang = lang.to_lowercase();
+        match self.lang_aliases.get(&lang) {
+            Some(alias) => alias.to_lowercase(),
+            None => lang,
+        }
+    }
+
+    fn tag_style(&self, hl_level: HighlightLevel, tag: Tag) -> Style {
+        let min_level = match tag {
+            Tag::Punctuation | Tag::Strong | Tag::Emph | Tag::Link => HighlightLevel::L1,
+            Tag::MathDelimiter | Tag::Operator | Tag::Function | Tag::Interpolated => {
+                HighlightLevel::L2
+            }
+            _ => HighlightLevel::Off,
         };
-        color
+        if hl_level < min_level {
+            return Style::new();
+        }
+
+        let mut style = *self.theme.get(tag);
+        if hl_level < HighlightLevel::WithStyles {
+            style.bold = false;
+            style.italic = false;
+            style.underline = false;
+        }
+        if self.discord && style.dimmed {
+            // Discord does not support dimmed text, so fall back to a dark color.
+            style.dimmed = false;
+            style.fg.get_or_insert(Color::Black);
+        }
+        style
     }
 }
 
-static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(two_face::syntax::extra_newlines);
-static THEME_SET: LazyLock<EmbeddedLazyThemeSet> = LazyLock::new(two_face::theme::extra);
+/// One attempt to render the input at a highlight level, see [`Highlighter::render_to`].
+struct RenderPass<'a> {
+    highlighter: &'a Highlighter,
+    node: &'a LinkedNode<'a>,
+    hl_level: HighlightLevel,
+}
 
-fn highlight_lang<W: WriteColor>(
-    input: &str,
-    lang: &str,
-    out: &mut DeferredWriter<W>,
-) -> Result<(), Error> {
-    let Some(syntax) = SYNTAX_SET.find_syntax_by_token(lang) else {
-        write!(out, "{input}")?;
-        return Ok(());
-    };
-    let ansi_theme = THEME_SET.get(EmbeddedThemeName::Base16);
-
-    let mut highlighter = HighlightLines::new(syntax, ansi_theme);
-    for line in LinesWithEndings::from(input) {
-        let ranges = highlighter.highlight_line(line, &SYNTAX_SET)?;
-        for (styles, text) in ranges {
-            let fg = styles.foreground;
-            let fg = convert_rgb_to_ansi_color(fg.r, fg.g, fg.b, fg.a);
-            let mut color = ColorSpec::new();
-            color.set_fg(fg);
-
-            let font_style = styles.font_style;
-            color.set_bold(font_style.contains(FontStyle::BOLD));
-            color.set_italic(font_style.contains(FontStyle::ITALIC));
-            color.set_underline(font_style.contains(FontStyle::UNDERLINE));
-
-            out.set_color(&color)?;
-            write!(out, "{text}")?;
-        }
+impl RenderPass<'_> {
+    /// Render the input using the given renderer and return the renderer.
+    fn render<R: Renderer>(&self, renderer: R) -> Result<R, Error> {
+        self.highlighter.render(self.node, renderer, self.hl_level)
     }
+}
 
-    Ok(())
+/// Collect the output of one of the `highlight_*_to` methods into a string.
+fn collect_string(write: impl FnOnce(&mut Vec<u8>) -> Result<(), Error>) -> Result<String, Error> {
+    let mut out = Vec::new();
+    write(&mut out)?;
+    Ok(String::from_utf8(out).expect("the output should be entirely UTF-8"))
 }
 
+fn parse(input: &str, mode: SyntaxMode) -> SyntaxNode {
+    match mode {
+        SyntaxMode::Code => typst_syntax::parse_code(input),
+        SyntaxMode::Markup => typst_syntax::parse(input),
+        SyntaxMode::Math => typst_syntax::parse_math(input),
+    }
+}
+
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(two_face::syntax::extra_newlines);
+static THEME_SET: LazyLock<EmbeddedLazyThemeSet> = LazyLock::new(two_face::theme::extra);
+
 /// Converts an RGB color from the theme to a [`Color`].
 ///
 /// Inspired by an equivalent function in `bat`[^1].
@@ -364,7 +1050,7 @@ fn convert_rgb_to_ansi_color(r: u8, g: u8, b: u8, a: u8) -> Option<Color> {
             _ => Color::Ansi256(r),
         }),
         1 => None,
-        _ => Some(Color::Ansi256(ansi_colours::ansi256_from_rgb((r, g, b)))),
+        _ => Some(Color::Rgb(r, g, b)),
     }
 }
 
@@ -397,68 +1083,86 @@ impl HighlightLevel {
     }
 }
 
-/// A writer that only sets the color when content is written.
-/// This is intended to lessen the size impact of unnecessary escape codes.
-struct DeferredWriter<W> {
-    inner: W,
-    current_color: ColorSpec,
-    next_color: Option<ColorSpec>,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl<W> DeferredWriter<W> {
-    fn new(writer: W) -> DeferredWriter<W> {
-        DeferredWriter {
-            inner: writer,
-            current_color: ColorSpec::new(),
-            next_color: None,
-        }
-    }
-}
+    #[test]
+    fn test_nested_typst() {
+        let input = "````typ\n#let x = 1\n```foo\n```\n````\n```typc calc.pow(2, 3)```";
+        let output = Highlighter::default().highlight(input).unwrap();
+        let number = Highlighter::default()
+            .with_syntax_mode(SyntaxMode::Code)
+            .highlight("2")
+            .unwrap();
+        assert!(output.contains(&number));
+        assert!(output.contains("#let"));
 
-impl<W: WriteColor> Write for DeferredWriter<W> {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        if let Some(color) = self.next_color.take() {
-            self.inner.set_color(&color)?;
-            self.current_color = color;
-        }
-        self.inner.write(buf)
+        let warnings = Highlighter::default().warnings(input);
+        assert_eq!(warnings, [Warning::UnknownLanguage("foo".to_string())]);
     }
 
-    fn flush(&mut self) -> std::io::Result<()> {
-        self.inner.flush()
+    #[test]
+    fn test_nested_typst_soft_limit() {
+        // Without highlighting, the language tag is still written exactly once.
+        let input = "```typ\n#let x = 1\n```";
+        let output = Highlighter::default()
+            .with_soft_limit(1)
+            .highlight(input)
+            .unwrap();
+        assert_eq!(output, format!("\x1b[0m\x1b[37m{input}"));
     }
-}
 
-impl<W: WriteColor> WriteColor for DeferredWriter<W> {
-    fn supports_color(&self) -> bool {
-        self.inner.supports_color()
-    }
+    #[test]
+    fn test_soft_limit() {
+        type Format = fn(&Highlighter, &str) -> Result<String, Error>;
+        let formats: &[(&str, Format)] = &[
+            ("ansi", Highlighter::highlight),
+            ("html", |highlighter, input| {
+                highlighter.highlight_html(input, HtmlStyle::Inline)
+            }),
+            ("svg", |highlighter, input| {
+                highlighter.highlight_svg(input, &SvgOptions::default())
+            }),
+            ("latex", Highlighter::highlight_latex),
+            ("rtf", Highlighter::highlight_rtf),
+            ("matrix", Highlighter::highlight_matrix),
+            ("irc", Highlighter::highlight_irc),
+            ("bbcode", |highlighter, input| {
+                highlighter.highlight_bbcode(input, None)
+            }),
+            ("pango", Highlighter::highlight_pango),
+            ("typst", Highlighter::highlight_typst),
+            ("unicode", Highlighter::highlight_unicode),
+        ];
 
-    fn set_color(&mut self, spec: &ColorSpec) -> std::io::Result<()> {
-        if &self.current_color == spec {
-            self.next_color = None;
-        } else {
-            self.next_color = Some(spec.clone());
+        // The limit applies to the whole output of each format, including any markup
+        // around the code, so output just reaching it has to be highlighted less.
+        let input = "= A\n*b* `c`";
+        for (name, highlight) in formats {
+            let full = highlight(&Highlighter::default(), input).unwrap();
+            let limited = highlight(Highlighter::default().with_soft_limit(full.len()), input);
+            assert!(limited.unwrap().len() < full.len(), "{name}");
         }
-        Ok(())
-    }
-
-    fn reset(&mut self) -> std::io::Result<()> {
-        let mut color = ColorSpec::new();
-        color.set_reset(true);
-        self.next_color = Some(color);
-        Ok(())
     }
 
-    fn is_synchronous(&self) -> bool {
-        self.inner.is_synchronous()
-    }
+    #[test]
+    fn test_spans() {
+        let input = "#let x = 1\n```rs\nfn a() {}\n```";
+        let spans = Highlighter::default().spans(input).unwrap();
+        for span in &spans {
+            assert_eq!(&input[span.range.clone()], span.text);
+        }
+        assert_eq!(spans.last().unwrap().range.end, input.len());
 
-    fn set_hyperlink(&mut self, link: &termcolor::HyperlinkSpec) -> std::io::Result<()> {
-        self.inner.set_hyperlink(link)
-    }
+        let number = spans.iter().find(|span| span.text == "1").unwrap();
+        assert_eq!(number.kind, Some(SyntaxKind::Int));
+        assert_eq!(number.tag, Some(Tag::Number));
+        assert_eq!(number.style, Theme::DEFAULT.number);
 
-    fn supports_hyperlinks(&self) -> bool {
-        self.inner.supports_hyperlinks()
+        let keyword = spans.iter().find(|span| span.text == "fn").unwrap();
+        assert_eq!(keyword.kind, None);
+        assert_eq!(keyword.tag, None);
+        assert_eq!(keyword.style.fg, Some(Color::Magenta));
     }
 }
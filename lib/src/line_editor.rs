@@ -0,0 +1,228 @@
+//! Highlighting the input of line editors while it is being typed.
+//!
+//! The line is highlighted again on every keystroke, so it is usually incomplete Typst code.
+//! The parser recovers from any syntax error, and the highlighted line always contains
+//! exactly the characters of the input, which the editors rely on to place the cursor.
+#[cfg(feature = "rustyline")]
+use std::borrow::Cow;
+#[cfg(feature = "reedline")]
+use std::io;
+
+#[cfg(feature = "reedline")]
+use typst_syntax::Tag;
+
+#[cfg(feature = "rustyline")]
+use crate::AnsiRenderer;
+use crate::Highlighter;
+#[cfg(feature = "reedline")]
+use crate::{render::Renderer, ColorDepth, Style};
+
+/// The highlighter to use for a line, which never wraps it in a code block for Discord.
+fn for_line(highlighter: Highlighter) -> Highlighter {
+    Highlighter {
+        discord: false,
+        ..highlighter
+    }
+}
+
+/// Highlights the input of a [`reedline`] editor.
+///
+/// Requires the `reedline` feature.
+///
+/// [`reedline`]: crate::ext::reedline
+#[cfg(feature = "reedline")]
+#[derive(Debug, Clone)]
+pub struct ReedlineHighlighter {
+    highlighter: Highlighter,
+}
+
+#[cfg(feature = "reedline")]
+impl ReedlineHighlighter {
+    /// Create a line highlighter using the settings of the given highlighter.
+    ///
+    /// [`Highlighter::for_discord`] and [`Highlighter::with_soft_limit`] are ignored.
+    pub fn new(highlighter: Highlighter) -> ReedlineHighlighter {
+        ReedlineHighlighter {
+            highlighter: for_line(highlighter),
+        }
+    }
+}
+
+#[cfg(feature = "reedline")]
+impl reedline::Highlighter for ReedlineHighlighter {
+    fn highlight(&self, line: &str, _: usize) -> reedline::StyledText {
+        let renderer = StyledTextRenderer {
+            color_depth: self.highlighter.color_depth,
+            style: nu_ansi_term::Style::new(),
+            text: reedline::StyledText::new(),
+        };
+        match self.highlighter.highlight_with(line, renderer) {
+            Ok(renderer) => renderer.text,
+            Err(_) => {
+                let mut text = reedline::StyledText::new();
+                text.push((nu_ansi_term::Style::new(), line.to_owned()));
+                text
+            }
+        }
+    }
+}
+
+/// Collects styled text for reedline.
+#[cfg(feature = "reedline")]
+struct StyledTextRenderer {
+    color_depth: ColorDepth,
+    style: nu_ansi_term::Style,
+    text: reedline::StyledText,
+}
+
+#[cfg(feature = "reedline")]
+impl StyledTextRenderer {
+    fn color(&self, color: termcolor::Color) -> nu_ansi_term::Color {
+        use nu_ansi_term::Color as NuColor;
+        use termcolor::Color;
+
+        match self.color_depth.quantize(color) {
+            Color::Black => NuColor::Black,
+            Color::Red => NuColor::Red,
+            Color::Green => NuColor::Green,
+            Color::Yellow => NuColor::Yellow,
+            Color::Blue => NuColor::Blue,
+            Color::Magenta => NuColor::Magenta,
+            Color::Cyan => NuColor::Cyan,
+            Color::White => NuColor::White,
+            Color::Ansi256(index) => NuColor::Fixed(index),
+            Color::Rgb(r, g, b) => NuColor::Rgb(r, g, b),
+            _ => NuColor::Default,
+        }
+    }
+}
+
+#[cfg(feature = "reedline")]
+impl Renderer for StyledTextRenderer {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        self.style = nu_ansi_term::Style {
+            foreground: style.fg.map(|fg| self.color(fg)),
+            background: style.bg.map(|bg| self.color(bg)),
+            is_bold: style.bold,
+            is_dimmed: style.dimmed,
+            is_italic: style.italic,
+            is_underline: style.underline,
+            ..nu_ansi_term::Style::new()
+        };
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        self.style = nu_ansi_term::Style::new();
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        self.text.push((self.style, text.to_owned()));
+        Ok(())
+    }
+}
+
+/// Highlights the input of a [`rustyline`] editor.
+///
+/// To use it with an editor, combine it with the other traits of a `rustyline::Helper`.
+///
+/// Requires the `rustyline` feature.
+///
+/// [`rustyline`]: crate::ext::rustyline
+#[cfg(feature = "rustyline")]
+#[derive(Debug, Clone)]
+pub struct RustylineHighlighter {
+    highlighter: Highlighter,
+}
+
+#[cfg(feature = "rustyline")]
+impl RustylineHighlighter {
+    /// Create a line highlighter using the settings of the given highlighter.
+    ///
+    /// [`Highlighter::for_discord`] and [`Highlighter::with_soft_limit`] are ignored.
+    pub fn new(highlighter: Highlighter) -> RustylineHighlighter {
+        RustylineHighlighter {
+            highlighter: for_line(highlighter),
+        }
+    }
+}
+
+#[cfg(feature = "rustyline")]
+impl rustyline::highlight::Highlighter for RustylineHighlighter {
+    fn highlight<'l>(&self, line: &'l str, _: usize) -> Cow<'l, str> {
+        let out = termcolor::Ansi::new(Vec::new());
+        let renderer = AnsiRenderer::new(out, self.highlighter.color_depth);
+        let Ok(renderer) = self.highlighter.highlight_with(line, renderer) else {
+            return Cow::Borrowed(line);
+        };
+        let mut out = renderer.into_inner().into_inner();
+        if out == line.as_bytes() {
+            return Cow::Borrowed(line);
+        }
+        // The style of the last token must not leak into what the editor writes after the line.
+        out.extend_from_slice(b"\x1b[0m");
+        Cow::Owned(String::from_utf8(out).expect("the output should be entirely UTF-8"))
+    }
+
+    fn highlight_char(&self, _: &str, _: usize, _: rustyline::highlight::CmdKind) -> bool {
+        // Any character may change how the rest of the line is parsed.
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Highlighter;
+
+    /// Incomplete prefixes of a line, as seen while typing it.
+    fn prefixes(line: &str) -> impl Iterator<Item = &str> {
+        line.char_indices().map(|(i, _)| &line[..i]).chain([line])
+    }
+
+    const LINE: &str = "#let f(x) = $x^2$ + \"ä\" // *_`raw` [a] ```rs fn";
+
+    #[cfg(feature = "reedline")]
+    #[test]
+    fn test_reedline_highlighter() {
+        use reedline::Highlighter as _;
+
+        use crate::ReedlineHighlighter;
+
+        let highlighter = ReedlineHighlighter::new(Highlighter::default());
+        for prefix in prefixes(LINE) {
+            let text = highlighter.highlight(prefix, prefix.len());
+            assert_eq!(text.raw_string(), prefix);
+        }
+
+        let text = highlighter.highlight("#let", 4);
+        assert_eq!(text.buffer[0].0, nu_ansi_term::Color::Magenta.normal());
+    }
+
+    #[cfg(feature = "rustyline")]
+    #[test]
+    fn test_rustyline_highlighter() {
+        use rustyline::highlight::Highlighter as _;
+
+        use crate::RustylineHighlighter;
+
+        let highlighter = RustylineHighlighter::new(Highlighter::default());
+        for prefix in prefixes(LINE) {
+            let output = highlighter.highlight(prefix, prefix.len());
+            let mut stripped = String::new();
+            let mut rest = &*output;
+            while let Some((text, escape)) = rest.split_once('\x1b') {
+                stripped.push_str(text);
+                rest = &escape[escape.find('m').unwrap() + 1..];
+            }
+            stripped.push_str(rest);
+            assert_eq!(stripped, prefix);
+        }
+
+        assert_eq!(
+            highlighter.highlight("#let", 4),
+            "\x1b[0m\x1b[35m#let\x1b[0m"
+        );
+        assert_eq!(highlighter.highlight("a", 1), "a");
+    }
+}
@@ -0,0 +1,163 @@
+//! Rendering highlighted code as a Matrix message.
+use std::io;
+
+use serde::Serialize;
+use typst_syntax::Tag;
+
+use crate::{
+    color::{hex, HexFormat},
+    html::escape,
+    render::Renderer,
+    Style,
+};
+
+/// Renders styles using the HTML subset allowed in the `formatted_body` of Matrix messages.
+///
+/// Colors are applied with `<font data-mx-color>` and `data-mx-bg-color`,
+/// and text styles with `<b>`, `<i>` and `<u>`.
+/// Dimmed text is not supported by Matrix clients, so it is rendered normally.
+pub struct MatrixRenderer<W> {
+    inner: W,
+    style: Style,
+}
+
+impl<W: io::Write> MatrixRenderer<W> {
+    /// Create a renderer writing to the given writer.
+    pub fn new(writer: W) -> MatrixRenderer<W> {
+        MatrixRenderer {
+            inner: writer,
+            style: Style::new(),
+        }
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: io::Write> Renderer for MatrixRenderer<W> {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        self.style = *style;
+        if style.fg.is_some() || style.bg.is_some() {
+            write!(self.inner, "<font")?;
+            if let Some(fg) = style.fg {
+                write!(self.inner, " data-mx-color=\"{}\"", hex(fg, HexFormat::Css))?;
+            }
+            if let Some(bg) = style.bg {
+                write!(
+                    self.inner,
+                    " data-mx-bg-color=\"{}\"",
+                    hex(bg, HexFormat::Css)
+                )?;
+            }
+            write!(self.inner, ">")?;
+        }
+        if style.bold {
+            write!(self.inner, "<b>")?;
+        }
+        if style.italic {
+            write!(self.inner, "<i>")?;
+        }
+        if style.underline {
+            write!(self.inner, "<u>")?;
+        }
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        let style = std::mem::replace(&mut self.style, Style::new());
+        if style.underline {
+            write!(self.inner, "</u>")?;
+        }
+        if style.italic {
+            write!(self.inner, "</i>")?;
+        }
+        if style.bold {
+            write!(self.inner, "</b>")?;
+        }
+        if style.fg.is_some() || style.bg.is_some() {
+            write!(self.inner, "</font>")?;
+        }
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        escape(&mut self.inner, text)
+    }
+
+    fn start_link(&mut self, url: &str) -> io::Result<()> {
+        write!(self.inner, "<a href=\"")?;
+        escape(&mut self.inner, url)?;
+        write!(self.inner, "\">")
+    }
+
+    fn end_link(&mut self) -> io::Result<()> {
+        write!(self.inner, "</a>")
+    }
+}
+
+/// The content of an `m.room.message` event.
+#[derive(Serialize)]
+pub(crate) struct Message<'a> {
+    msgtype: &'static str,
+    body: String,
+    format: &'static str,
+    formatted_body: &'a str,
+}
+
+impl<'a> Message<'a> {
+    /// A text message showing the input as a code block,
+    /// which clients without HTML support display as a Markdown code block.
+    pub(crate) fn new(input: &str, formatted_body: &'a str) -> Message<'a> {
+        let newline = if input.ends_with('\n') { "" } else { "\n" };
+        // The fence has to be longer than any run of backticks in the input,
+        // so that raw blocks don't end the code block early.
+        let longest = input.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+        let fence = "`".repeat(longest.max(2) + 1);
+        Message {
+            msgtype: "m.text",
+            body: format!("{fence}typst\n{input}{newline}{fence}"),
+            format: "org.matrix.custom.html",
+            formatted_body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::Highlighter;
+
+    #[test]
+    fn test_highlight_matrix() {
+        let input = "= A & B\nhttps://typst.app";
+        let output = Highlighter::default().highlight_matrix(input).unwrap();
+        let output: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "msgtype": "m.text",
+                "body": "```typst\n= A & B\nhttps://typst.app\n```",
+                "format": "org.matrix.custom.html",
+                "formatted_body": "<pre><code>\
+                    <font data-mx-color=\"#00cdcd\"><b>=</b></font> A &amp; B\n\
+                    <a href=\"https://typst.app\"><font data-mx-color=\"#0000ee\"><u>https://typst.app</u></font></a>\
+                    </code></pre>",
+            })
+        );
+    }
+
+    #[test]
+    fn test_matrix_body_fence() {
+        let output = Highlighter::default()
+            .highlight_matrix("a\n````\n```rs\n```\n````\n")
+            .unwrap();
+        let output: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(
+            output["body"],
+            "`````typst\na\n````\n```rs\n```\n````\n`````"
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Rendering highlighted code as Pango markup.
+use std::io;
+
+use typst_syntax::Tag;
+
+use crate::{
+    color::{hex, HexFormat},
+    html::escape,
+    render::Renderer,
+    Style,
+};
+
+/// Renders styles as `<span>` elements with Pango attributes.
+///
+/// Links are rendered as plain text, as most users of Pango markup don't support them.
+///
+/// Unlike [`Highlighter::highlight_pango`], this doesn't wrap the output in `<tt>`.
+///
+/// [`Highlighter::highlight_pango`]: crate::Highlighter::highlight_pango
+pub struct PangoRenderer<W> {
+    inner: W,
+}
+
+impl<W: io::Write> PangoRenderer<W> {
+    /// Create a renderer writing to the given writer.
+    pub fn new(writer: W) -> PangoRenderer<W> {
+        PangoRenderer { inner: writer }
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: io::Write> Renderer for PangoRenderer<W> {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        write!(self.inner, "<span")?;
+        if let Some(fg) = style.fg {
+            write!(self.inner, " foreground=\"{}\"", hex(fg, HexFormat::Css))?;
+        }
+        if let Some(bg) = style.bg {
+            write!(self.inner, " background=\"{}\"", hex(bg, HexFormat::Css))?;
+        }
+        if style.bold {
+            write!(self.inner, " weight=\"bold\"")?;
+        }
+        if style.italic {
+            write!(self.inner, " style=\"italic\"")?;
+        }
+        if style.underline {
+            write!(self.inner, " underline=\"single\"")?;
+        }
+        if style.dimmed {
+            write!(self.inner, " alpha=\"60%\"")?;
+        }
+        write!(self.inner, ">")
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        write!(self.inner, "</span>")
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        escape(&mut self.inner, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Highlighter;
+
+    #[test]
+    fn test_highlight_pango() {
+        let output = Highlighter::default()
+            .highlight_pango("a < b & *c*")
+            .unwrap();
+        assert_eq!(
+            output,
+            "<tt>a &lt; b &amp; \
+             <span foreground=\"#cdcd00\" weight=\"bold\">*</span>c*</tt>"
+        );
+    }
+}
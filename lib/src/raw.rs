@@ -0,0 +1,213 @@
+//! Configuration of how raw blocks with a language tag are highlighted.
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use syntect::{
+    highlighting::ThemeSet,
+    parsing::{SyntaxSet, SyntaxSetBuilder},
+};
+use two_face::theme::{EmbeddedLazyThemeSet, EmbeddedThemeName};
+
+use crate::{Error, THEME_SET};
+
+/// Language tags people commonly use for which no syntax is found by name or extension.
+const DEFAULT_LANG_ALIASES: &[(&str, &str)] = &[
+    ("c#", "cs"),
+    ("console", "bash"),
+    ("csharp", "cs"),
+    ("docker", "dockerfile"),
+    ("elisp", "lisp"),
+    ("emacs-lisp", "lisp"),
+    ("golang", "go"),
+    ("json5", "json"),
+    ("jsonc", "json"),
+    ("jsx", "js"),
+    ("objc", "objective-c"),
+    ("plain", "txt"),
+    ("plaintext", "txt"),
+    ("py3", "python"),
+    ("sh-session", "bash"),
+    ("shell", "bash"),
+    ("shell-session", "bash"),
+    ("terminal", "bash"),
+    ("text", "txt"),
+    ("typst", "typ"),
+];
+
+pub(crate) fn default_lang_aliases() -> HashMap<String, String> {
+    DEFAULT_LANG_ALIASES
+        .iter()
+        .map(|&(alias, lang)| (alias.to_owned(), lang.to_owned()))
+        .collect()
+}
+
+/// Load all `.sublime-syntax` files in a directory.
+///
+/// Use the result with [`Highlighter::with_syntax_set`] to highlight raw blocks in
+/// languages not supported by default.
+/// Only the syntaxes in the directory are built, which is much faster than rebuilding
+/// the default syntaxes, but means that they can't include any of the default syntaxes.
+///
+/// [`Highlighter::with_syntax_set`]: crate::Highlighter::with_syntax_set
+pub fn load_syntaxes(dir: &Path) -> Result<SyntaxSet, Error> {
+    let mut builder = SyntaxSetBuilder::new();
+    builder.add_from_folder(dir, true)?;
+    Ok(builder.build())
+}
+
+/// The syntect theme used to highlight raw blocks with a language tag.
+#[derive(Debug, Clone)]
+pub enum RawTheme {
+    /// One of the themes embedded in two-face.
+    Embedded(EmbeddedThemeName),
+    /// A custom theme, e.g. loaded from a `.tmTheme` file.
+    Custom(Arc<syntect::highlighting::Theme>),
+}
+
+impl RawTheme {
+    /// Load a theme from a `.tmTheme` file.
+    pub fn load(path: &Path) -> Result<RawTheme, Error> {
+        Ok(RawTheme::Custom(Arc::new(ThemeSet::get_theme(path)?)))
+    }
+
+    /// Look up an embedded theme by its name, such as `Nord` or `gruvbox-dark`.
+    pub fn embedded(name: &str) -> Option<RawTheme> {
+        EmbeddedLazyThemeSet::theme_names()
+            .iter()
+            .find(|theme| theme.as_name().eq_ignore_ascii_case(name))
+            .map(|&theme| RawTheme::Embedded(theme))
+    }
+
+    pub(crate) fn get(&self) -> &syntect::highlighting::Theme {
+        match self {
+            RawTheme::Embedded(name) => THEME_SET.get(*name),
+            RawTheme::Custom(theme) => theme,
+        }
+    }
+}
+
+impl Default for RawTheme {
+    fn default() -> Self {
+        RawTheme::Embedded(EmbeddedThemeName::Base16)
+    }
+}
+
+impl From<EmbeddedThemeName> for RawTheme {
+    fn from(name: EmbeddedThemeName) -> Self {
+        RawTheme::Embedded(name)
+    }
+}
+
+impl From<syntect::highlighting::Theme> for RawTheme {
+    fn from(theme: syntect::highlighting::Theme) -> Self {
+        RawTheme::Custom(Arc::new(theme))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ColorDepth, Highlighter, Warning};
+
+    const SYNTAX: &str = r#"%YAML 1.2
+---
+name: Tiny
+file_extensions: [tiny]
+scope: source.tiny
+contexts:
+  main:
+    - match: '\bfoo\b'
+      scope: keyword.control.tiny
+"#;
+
+    const THEME: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>name</key>
+    <string>Test</string>
+    <key>settings</key>
+    <array>
+        <dict>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#102030</string>
+            </dict>
+        </dict>
+        <dict>
+            <key>scope</key>
+            <string>storage, keyword</string>
+            <key>settings</key>
+            <dict>
+                <key>foreground</key>
+                <string>#C08040</string>
+            </dict>
+        </dict>
+    </array>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn test_load_syntaxes() {
+        let dir = std::env::temp_dir().join("typst-ansi-hl-test-syntaxes");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("tiny.sublime-syntax"), SYNTAX).unwrap();
+        let syntax_set = load_syntaxes(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let mut highlighter = Highlighter::default();
+        highlighter.with_syntax_set(syntax_set);
+        let input = "```tiny\nfoo bar\n```\n```rs\nfn\n```";
+        assert_eq!(highlighter.warnings(input), []);
+        let output = highlighter.highlight(input).unwrap();
+        assert!(output.contains("\x1b[35mfoo\x1b[0m\x1b[37m bar"));
+        // The default syntaxes are still available.
+        assert!(output.contains("\x1b[35mfn"));
+    }
+
+    #[test]
+    fn test_lang_aliases() {
+        let mut highlighter = Highlighter::default();
+        highlighter.with_lang_alias("MyLang", "rust");
+        let input = "```mylang\nfn\n```\n```JSONC\n1\n```\n```Console\n$ ls\n```";
+        assert_eq!(highlighter.warnings(input), []);
+
+        for (alias, _) in DEFAULT_LANG_ALIASES {
+            let input = format!("```{alias}\n```");
+            assert_eq!(Highlighter::default().warnings(&input), [], "{alias}");
+        }
+
+        assert_eq!(
+            highlighter.warnings("```NoLang\n```"),
+            [Warning::UnknownLanguage("NoLang".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_embedded_raw_theme() {
+        assert!(matches!(
+            RawTheme::embedded("nord"),
+            Some(RawTheme::Embedded(EmbeddedThemeName::Nord))
+        ));
+        assert!(RawTheme::embedded("no-such-theme").is_none());
+    }
+
+    #[test]
+    fn test_load_raw_theme() {
+        let path = std::env::temp_dir().join("typst-ansi-hl-test-raw-theme.tmTheme");
+        std::fs::write(&path, THEME).unwrap();
+        let theme = RawTheme::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let highlight = |color_depth| {
+            Highlighter::default()
+                .with_raw_theme(theme.clone())
+                .with_color_depth(color_depth)
+                .highlight("```rs\nfn a() {}\n```")
+                .unwrap()
+        };
+        // Colors of the theme are passed through unchanged at true color depth.
+        assert!(highlight(ColorDepth::TrueColor).contains("\x1b[38;2;192;128;64mfn"));
+        assert!(highlight(ColorDepth::Ansi256).contains("\x1b[38;5;137mfn"));
+    }
+}
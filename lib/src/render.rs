@@ -0,0 +1,349 @@
+//! Turning styled text into an output format.
+use std::{io, ops::Range};
+
+use termcolor::{ColorSpec, HyperlinkSpec, WriteColor};
+use typst_syntax::{SyntaxKind, Tag};
+
+use crate::{ColorDepth, Style};
+
+/// A sink for styled text, implemented once per output format.
+///
+/// Pass a renderer to [`Highlighter::highlight_with`] to produce output in your own format.
+///
+/// Styles are never nested: each [`Renderer::start_style`] is followed by text
+/// and exactly one [`Renderer::end_style`] before the next style starts.
+/// Text outside of any style is unstyled.
+/// Links are never nested either and no style spans across the start or end of a link.
+///
+/// ```
+/// # use std::io;
+/// # use typst_ansi_hl::{ext::typst_syntax::Tag, Highlighter, Renderer, Style};
+/// /// Marks keywords with asterisks.
+/// struct Keywords(String);
+///
+/// impl Renderer for Keywords {
+///     fn start_style(&mut self, _: &Style, tag: Option<Tag>) -> io::Result<()> {
+///         if tag == Some(Tag::Keyword) {
+///             self.0.push('*');
+///         }
+///         Ok(())
+///     }
+///
+///     fn end_style(&mut self) -> io::Result<()> {
+///         if self.0.ends_with(|c: char| c.is_alphabetic()) {
+///             self.0.push('*');
+///         }
+///         Ok(())
+///     }
+///
+///     fn text(&mut self, text: &str) -> io::Result<()> {
+///         self.0.push_str(text);
+///         Ok(())
+///     }
+/// }
+///
+/// let output = Highlighter::default().highlight_with("#let x", Keywords(String::new()))?;
+/// assert_eq!(output.0, "*#let* x");
+/// # Ok::<(), typst_ansi_hl::Error>(())
+/// ```
+///
+/// [`Highlighter::highlight_with`]: crate::Highlighter::highlight_with
+pub trait Renderer {
+    /// Start a span of text with the given style.
+    ///
+    /// The tag is the kind of Typst token the span belongs to,
+    /// or `None` for text highlighted by syntect.
+    fn start_style(&mut self, style: &Style, tag: Option<Tag>) -> io::Result<()>;
+
+    /// End the span started by the last [`Renderer::start_style`].
+    fn end_style(&mut self) -> io::Result<()>;
+
+    /// Write text in the current style.
+    fn text(&mut self, text: &str) -> io::Result<()>;
+
+    /// Start a link to the given URL.
+    ///
+    /// By default, links are ignored.
+    fn start_link(&mut self, url: &str) -> io::Result<()> {
+        let _ = url;
+        Ok(())
+    }
+
+    /// End the link started by the last [`Renderer::start_link`].
+    fn end_link(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A piece of the input together with how it was classified and styled.
+///
+/// Returned by [`Highlighter::spans`].
+///
+/// [`Highlighter::spans`]: crate::Highlighter::spans
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledSpan {
+    /// The byte range of the span in the input.
+    pub range: Range<usize>,
+    /// The text of the span.
+    pub text: String,
+    /// The kind of the syntax node the text belongs to,
+    /// or `None` for text highlighted by syntect.
+    pub kind: Option<SyntaxKind>,
+    /// The highlighting tag the style was determined by,
+    /// or `None` for unhighlighted text and text highlighted by syntect.
+    pub tag: Option<Tag>,
+    /// The style the text is rendered with.
+    pub style: Style,
+}
+
+/// Sits between the highlighter and a [`Renderer`] and only starts a style
+/// once text is written in it.
+/// This avoids empty spans and merges neighbouring spans of the same style and tag.
+///
+/// It can also record every piece of text written to it as a [`StyledSpan`].
+pub(crate) struct Emitter<R> {
+    renderer: R,
+    current: Option<(Style, Option<Tag>)>,
+    next: (Style, Option<Tag>),
+    spans: Option<Vec<StyledSpan>>,
+    offset: usize,
+}
+
+impl<R: Renderer> Emitter<R> {
+    pub(crate) fn new(renderer: R) -> Emitter<R> {
+        Emitter {
+            renderer,
+            current: None,
+            next: (Style::new(), None),
+            spans: None,
+            offset: 0,
+        }
+    }
+
+    /// Record all text written from now on.
+    pub(crate) fn record_spans(&mut self) {
+        self.spans = Some(Vec::new());
+    }
+
+    /// Use the given style for all following text.
+    pub(crate) fn set_style(&mut self, style: Style, tag: Option<Tag>) {
+        self.next = (style, tag);
+    }
+
+    /// Write text in the style that was set last.
+    pub(crate) fn text(&mut self, text: &str) -> io::Result<()> {
+        self.write(None, text)
+    }
+
+    /// Write the text of a syntax node in the style that was set last.
+    pub(crate) fn node_text(&mut self, kind: SyntaxKind, text: &str) -> io::Result<()> {
+        self.write(Some(kind), text)
+    }
+
+    /// Write the text of a link node in the style that was set last.
+    pub(crate) fn link(&mut self, url: &str, text: &str) -> io::Result<()> {
+        self.end_style()?;
+        self.renderer.start_link(url)?;
+        self.write(Some(SyntaxKind::Link), text)?;
+        self.end_style()?;
+        self.renderer.end_link()
+    }
+
+    fn write(&mut self, kind: Option<SyntaxKind>, text: &str) -> io::Result<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        if let Some(spans) = &mut self.spans {
+            let (style, tag) = self.next;
+            spans.push(StyledSpan {
+                range: self.offset..self.offset + text.len(),
+                text: text.to_owned(),
+                kind,
+                tag,
+                style,
+            });
+            self.offset += text.len();
+        }
+
+        let next = (!self.next.0.is_plain()).then_some(self.next);
+        if self.current != next {
+            self.end_style()?;
+            if let Some((style, tag)) = &next {
+                self.renderer.start_style(style, *tag)?;
+            }
+            self.current = next;
+        }
+        self.renderer.text(text)
+    }
+
+    /// Return the recorded spans.
+    pub(crate) fn take_spans(&mut self) -> Vec<StyledSpan> {
+        self.spans.take().unwrap_or_default()
+    }
+
+    /// End the current style and return the renderer.
+    pub(crate) fn finish(mut self) -> io::Result<R> {
+        self.end_style()?;
+        Ok(self.renderer)
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        if self.current.take().is_some() {
+            self.renderer.end_style()?;
+        }
+        Ok(())
+    }
+}
+
+/// A renderer that discards everything, used when only spans are needed.
+pub(crate) struct Discard;
+
+impl Renderer for Discard {
+    fn start_style(&mut self, _: &Style, _: Option<Tag>) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn text(&mut self, _: &str) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Renders styles using ANSI escape sequences through [`termcolor`].
+///
+/// All colors are converted to the given color depth.
+/// This is the renderer used by [`Highlighter::highlight_to`].
+///
+/// [`Highlighter::highlight_to`]: crate::Highlighter::highlight_to
+pub struct AnsiRenderer<W> {
+    inner: W,
+    color_depth: ColorDepth,
+    hyperlinks: bool,
+    current_color: ColorSpec,
+    reset: bool,
+}
+
+impl<W: WriteColor> AnsiRenderer<W> {
+    /// Create a renderer writing to the given writer.
+    pub fn new(writer: W, color_depth: ColorDepth) -> AnsiRenderer<W> {
+        AnsiRenderer {
+            inner: writer,
+            color_depth,
+            hyperlinks: false,
+            current_color: ColorSpec::new(),
+            reset: false,
+        }
+    }
+
+    /// Write links as OSC 8 hyperlinks, if the writer supports them.
+    ///
+    /// Default: `false`, because many terminals and Discord don't support them.
+    pub fn with_hyperlinks(mut self) -> Self {
+        self.hyperlinks = true;
+        self
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Set the color of the writer.
+    ///
+    /// termcolor writes bright colors as 256-color escapes, even when they are given as
+    /// intense basic colors, so they are written as SGR 90–97 and 100–107 instead.
+    fn set_color(&mut self, spec: &ColorSpec) -> io::Result<()> {
+        let fg = spec
+            .fg()
+            .and_then(|&color| self.color_depth.bright_index(color));
+        let bg = spec
+            .bg()
+            .and_then(|&color| self.color_depth.bright_index(color));
+        if (fg.is_none() && bg.is_none()) || !self.inner.supports_color() {
+            return self.inner.set_color(spec);
+        }
+
+        let mut spec = spec.clone();
+        if fg.is_some() {
+            spec.set_fg(None);
+        }
+        if bg.is_some() {
+            spec.set_bg(None);
+        }
+        self.inner.set_color(&spec)?;
+        if let Some(index) = fg {
+            write!(self.inner, "\x1b[{}m", 90 + index)?;
+        }
+        if let Some(index) = bg {
+            write!(self.inner, "\x1b[{}m", 100 + index)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: WriteColor> Renderer for AnsiRenderer<W> {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        let color = self.color_depth.quantize_spec(&(*style).into());
+        // Setting a color already resets the previous one,
+        // so a pending reset is only written when followed by plain text.
+        self.reset = false;
+        if color != self.current_color {
+            self.set_color(&color)?;
+            self.current_color = color;
+        }
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        self.reset = true;
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        if self.reset {
+            self.inner.reset()?;
+            self.current_color = ColorSpec::new();
+            self.reset = false;
+        }
+        self.inner.write_all(text.as_bytes())
+    }
+
+    fn start_link(&mut self, url: &str) -> io::Result<()> {
+        if self.hyperlinks && self.inner.supports_hyperlinks() {
+            self.inner
+                .set_hyperlink(&HyperlinkSpec::open(url.as_bytes()))?;
+        }
+        Ok(())
+    }
+
+    fn end_link(&mut self) -> io::Result<()> {
+        if self.hyperlinks && self.inner.supports_hyperlinks() {
+            self.inner.set_hyperlink(&HyperlinkSpec::close())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Highlighter;
+
+    #[test]
+    fn test_hyperlinks() {
+        let renderer = AnsiRenderer::new(termcolor::Ansi::new(Vec::new()), ColorDepth::Ansi8)
+            .with_hyperlinks();
+        let out = Highlighter::default()
+            .highlight_with("See https://typst.app.", renderer)
+            .unwrap()
+            .into_inner()
+            .into_inner();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "See \x1b]8;;https://typst.app\x1b\\\x1b[0m\x1b[4m\x1b[34mhttps://typst.app\x1b]8;;\x1b\\\x1b[0m."
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Rendering highlighted code as a Rich Text Format document.
+use std::{fmt::Write as _, io};
+
+use termcolor::Color;
+use typst_syntax::Tag;
+
+use crate::{color::to_rgb, render::Renderer, Style, Theme};
+
+/// Collects styled text and writes it as an RTF document with a monospace font.
+///
+/// Nothing is written until [`RtfRenderer::finish`] is called,
+/// because the color table at the start of the document depends on all of the text.
+pub struct RtfRenderer {
+    colors: Vec<(u8, u8, u8)>,
+    body: String,
+}
+
+impl RtfRenderer {
+    /// Create a renderer whose color table starts with the colors of the theme.
+    pub fn new(theme: &Theme) -> RtfRenderer {
+        let mut renderer = RtfRenderer {
+            colors: Vec::new(),
+            body: String::new(),
+        };
+        for &tag in Tag::LIST {
+            let style = theme.get(tag);
+            for color in [style.fg, style.bg].into_iter().flatten() {
+                renderer.color_index(to_rgb(color));
+            }
+        }
+        renderer
+    }
+
+    /// Write the RTF document to the given output.
+    pub fn finish<W: io::Write>(self, mut out: W) -> io::Result<()> {
+        writeln!(out, "{{\\rtf1\\ansi\\deff0")?;
+        writeln!(out, "{{\\fonttbl{{\\f0\\fmodern Courier New;}}}}")?;
+        write!(out, "{{\\colortbl;")?;
+        for (r, g, b) in &self.colors {
+            write!(out, "\\red{r}\\green{g}\\blue{b};")?;
+        }
+        writeln!(out, "}}")?;
+        writeln!(out, "\\f0\\fs20")?;
+        write!(out, "{}", self.body)?;
+        writeln!(out, "}}")
+    }
+
+    /// The index of a color in the color table, adding it if necessary.
+    ///
+    /// Index 0 is reserved for the default color.
+    fn color_index(&mut self, rgb: (u8, u8, u8)) -> usize {
+        let index = match self.colors.iter().position(|&color| color == rgb) {
+            Some(index) => index,
+            None => {
+                self.colors.push(rgb);
+                self.colors.len() - 1
+            }
+        };
+        index + 1
+    }
+}
+
+impl Renderer for RtfRenderer {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        let mut words = String::from("{");
+        let fg = match (style.fg, style.dimmed) {
+            (Some(fg), false) => Some(to_rgb(fg)),
+            // Word processors have no dimmed text, so mix the color with the white page.
+            (Some(fg), true) => Some(mix_with_white(to_rgb(fg))),
+            (None, true) => Some(mix_with_white(to_rgb(Color::Black))),
+            (None, false) => None,
+        };
+        if let Some(fg) = fg {
+            write!(words, "\\cf{}", self.color_index(fg)).unwrap();
+        }
+        if let Some(bg) = style.bg {
+            write!(words, "\\highlight{}", self.color_index(to_rgb(bg))).unwrap();
+        }
+        if style.bold {
+            words.push_str("\\b");
+        }
+        if style.italic {
+            words.push_str("\\i");
+        }
+        if style.underline {
+            words.push_str("\\ul");
+        }
+        words.push(' ');
+        self.body.push_str(&words);
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        self.body.push('}');
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        for c in text.chars() {
+            match c {
+                '\\' | '{' | '}' => write!(self.body, "\\{c}").unwrap(),
+                '\n' => self.body.push_str("\\line\n"),
+                '\r' => {}
+                '\t' => self.body.push_str("\\tab "),
+                ' '..='~' => self.body.push(c),
+                _ => {
+                    // Control words take signed 16-bit numbers,
+                    // and characters outside of the BMP are written as surrogate pairs.
+                    for unit in c.encode_utf16(&mut [0; 2]) {
+                        write!(self.body, "\\u{}?", *unit as i16).unwrap();
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn mix_with_white((r, g, b): (u8, u8, u8)) -> (u8, u8, u8) {
+    let mix = |c: u8| ((u16::from(c) * 6 + 255 * 4) / 10) as u8;
+    (mix(r), mix(g), mix(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Highlighter;
+
+    #[test]
+    fn test_highlight_rtf() {
+        let output = Highlighter::default().highlight_rtf("*{ä}*\n😀").unwrap();
+        assert!(output.starts_with("{\\rtf1\\ansi"));
+        assert!(output.contains("{\\colortbl;\\red0\\green205\\blue205;\\red205\\green205\\blue0;"));
+        assert!(output.contains("{\\cf2\\b *}\\{\\u228?\\}*\\line\n\\u-10179?\\u-8704?"));
+        assert!(output.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,272 @@
+//! Rendering highlighted code as an SVG image.
+use std::io;
+
+use termcolor::Color;
+use typst_syntax::Tag;
+
+use crate::{
+    color::{hex, HexFormat},
+    html::escape,
+    render::Renderer,
+    Style,
+};
+
+const FONT_SIZE: f32 = 14.0;
+/// The advance of a character of a typical monospace font.
+const CHAR_WIDTH: f32 = FONT_SIZE * 0.6;
+const LINE_HEIGHT: f32 = FONT_SIZE * 1.5;
+const PADDING: f32 = 16.0;
+const TITLE_BAR_HEIGHT: f32 = 32.0;
+const TAB_WIDTH: usize = 4;
+
+/// Options for SVG output, see [`Highlighter::highlight_svg`].
+///
+/// [`Highlighter::highlight_svg`]: crate::Highlighter::highlight_svg
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// Show line numbers in front of each line.
+    pub line_numbers: bool,
+    /// Show a title bar with the given title above the code.
+    /// If empty, the title bar has no text.
+    pub title: Option<String>,
+    /// The color of the background.
+    pub background: Color,
+    /// The color of text without a color.
+    pub foreground: Color,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            line_numbers: false,
+            title: None,
+            background: Color::Rgb(0x1e, 0x1e, 0x1e),
+            foreground: Color::Rgb(0xd4, 0xd4, 0xd4),
+        }
+    }
+}
+
+/// A piece of a line in a single style.
+struct Segment {
+    style: Option<Style>,
+    text: String,
+}
+
+/// Collects styled lines and lays them out as `<text>` and `<tspan>` elements.
+///
+/// Unlike other renderers, nothing is written until [`SvgRenderer::finish`] is called,
+/// because the size of the image depends on all of the text.
+pub struct SvgRenderer {
+    options: SvgOptions,
+    lines: Vec<Vec<Segment>>,
+    style: Option<Style>,
+}
+
+impl SvgRenderer {
+    /// Create a renderer with the given options.
+    pub fn new(options: SvgOptions) -> SvgRenderer {
+        SvgRenderer {
+            options,
+            lines: vec![Vec::new()],
+            style: None,
+        }
+    }
+
+    /// Write the SVG image to the given output.
+    pub fn finish<W: io::Write>(mut self, mut out: W) -> io::Result<()> {
+        // A trailing newline doesn't start another line.
+        if self.lines.len() > 1 && self.lines.last().is_some_and(Vec::is_empty) {
+            self.lines.pop();
+        }
+
+        let gutter = match self.options.line_numbers {
+            true => self.lines.len().to_string().len() + 2,
+            false => 0,
+        };
+        let columns = self
+            .lines
+            .iter()
+            .map(|line| line.iter().map(|s| s.text.chars().count()).sum::<usize>())
+            .max()
+            .unwrap_or(0);
+        let title_bar = match self.options.title {
+            Some(_) => TITLE_BAR_HEIGHT,
+            None => 0.0,
+        };
+        let width = 2.0 * PADDING + (gutter + columns) as f32 * CHAR_WIDTH;
+        let height = 2.0 * PADDING + title_bar + self.lines.len() as f32 * LINE_HEIGHT;
+
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" \
+             width=\"{width:.1}\" height=\"{height:.1}\" viewBox=\"0 0 {width:.1} {height:.1}\">"
+        )?;
+        writeln!(
+            out,
+            "<rect width=\"100%\" height=\"100%\" rx=\"6\" fill=\"{}\"/>",
+            hex(self.options.background, HexFormat::Css)
+        )?;
+
+        if let Some(title) = &self.options.title {
+            for (i, color) in ["#ff5f56", "#ffbd2e", "#27c93f"].iter().enumerate() {
+                let x = PADDING + 6.0 + i as f32 * 20.0;
+                let y = TITLE_BAR_HEIGHT / 2.0 + 2.0;
+                writeln!(
+                    out,
+                    "<circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"6\" fill=\"{color}\"/>"
+                )?;
+            }
+            if !title.is_empty() {
+                write!(
+                    out,
+                    "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" font-family=\"sans-serif\" \
+                     font-size=\"{FONT_SIZE}\" fill=\"{}\" fill-opacity=\"0.6\">",
+                    width / 2.0,
+                    TITLE_BAR_HEIGHT / 2.0 + FONT_SIZE / 2.0,
+                    hex(self.options.foreground, HexFormat::Css),
+                )?;
+                escape(&mut out, title)?;
+                writeln!(out, "</text>")?;
+            }
+        }
+
+        writeln!(
+            out,
+            "<g font-family=\"ui-monospace, SFMono-Regular, Menlo, Consolas, monospace\" \
+             font-size=\"{FONT_SIZE}\" fill=\"{}\" xml:space=\"preserve\">",
+            hex(self.options.foreground, HexFormat::Css)
+        )?;
+        for (i, line) in self.lines.iter().enumerate() {
+            // The baseline sits about a fifth of the line height above the bottom.
+            let y = PADDING + title_bar + (i + 1) as f32 * LINE_HEIGHT - LINE_HEIGHT / 5.0;
+            if self.options.line_numbers {
+                let x = PADDING + (gutter - 2) as f32 * CHAR_WIDTH;
+                writeln!(
+                    out,
+                    "<text x=\"{x:.1}\" y=\"{y:.1}\" text-anchor=\"end\" fill-opacity=\"0.5\">{}</text>",
+                    i + 1
+                )?;
+            }
+
+            // Backgrounds have to be drawn as rectangles before the text.
+            let mut column = gutter;
+            for segment in line {
+                let len = segment.text.chars().count();
+                if let Some(bg) = segment.style.and_then(|style| style.bg) {
+                    writeln!(
+                        out,
+                        "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{LINE_HEIGHT:.1}\" fill=\"{}\"/>",
+                        PADDING + column as f32 * CHAR_WIDTH,
+                        PADDING + title_bar + i as f32 * LINE_HEIGHT,
+                        len as f32 * CHAR_WIDTH,
+                        hex(bg, HexFormat::Css),
+                    )?;
+                }
+                column += len;
+            }
+
+            write!(
+                out,
+                "<text x=\"{:.1}\" y=\"{y:.1}\">",
+                PADDING + gutter as f32 * CHAR_WIDTH
+            )?;
+            for segment in line {
+                match segment.style {
+                    Some(style) => {
+                        write!(out, "<tspan{}>", attributes(&style))?;
+                        escape(&mut out, &segment.text)?;
+                        write!(out, "</tspan>")?;
+                    }
+                    None => escape(&mut out, &segment.text)?,
+                }
+            }
+            writeln!(out, "</text>")?;
+        }
+        writeln!(out, "</g>")?;
+        writeln!(out, "</svg>")
+    }
+
+    fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let line = self.lines.last_mut().expect("there is always a line");
+        match line.last_mut() {
+            Some(segment) if segment.style == self.style => segment.text.push_str(text),
+            _ => line.push(Segment {
+                style: self.style,
+                text: text.to_owned(),
+            }),
+        }
+    }
+}
+
+impl Renderer for SvgRenderer {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        self.style = Some(*style);
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        self.style = None;
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        let text = text.replace('\t', &" ".repeat(TAB_WIDTH));
+        let mut lines = text.split('\n');
+        if let Some(first) = lines.next() {
+            self.push(first.trim_end_matches('\r'));
+        }
+        for line in lines {
+            self.lines.push(Vec::new());
+            self.push(line.trim_end_matches('\r'));
+        }
+        Ok(())
+    }
+}
+
+/// The presentation attributes for a style.
+fn attributes(style: &Style) -> String {
+    let mut attributes = String::new();
+    if let Some(fg) = style.fg {
+        attributes.push_str(&format!(" fill=\"{}\"", hex(fg, HexFormat::Css)));
+    }
+    if style.bold {
+        attributes.push_str(" font-weight=\"bold\"");
+    }
+    if style.italic {
+        attributes.push_str(" font-style=\"italic\"");
+    }
+    if style.underline {
+        attributes.push_str(" text-decoration=\"underline\"");
+    }
+    if style.dimmed {
+        attributes.push_str(" fill-opacity=\"0.6\"");
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Highlighter, SvgOptions};
+
+    #[test]
+    fn test_highlight_svg() {
+        let options = SvgOptions {
+            line_numbers: true,
+            title: Some("a & b".to_owned()),
+            ..SvgOptions::default()
+        };
+        let output = Highlighter::default()
+            .highlight_svg("= Hi\n1 < 2\n", &options)
+            .unwrap();
+        assert!(output.starts_with("<svg "));
+        assert!(output.ends_with("</svg>\n"));
+        assert!(output.contains(">a &amp; b</text>"));
+        assert!(output.contains("<tspan fill=\"#00cdcd\" font-weight=\"bold\">=</tspan> Hi</text>"));
+        assert!(output.contains(">1 &lt; 2</text>"));
+        assert!(output.contains("text-anchor=\"end\" fill-opacity=\"0.5\">2</text>"));
+        assert!(!output.contains(">3</text>"));
+    }
+}
@@ -0,0 +1,626 @@
+//! Themes determine which style is used for each kind of token.
+use std::{path::Path, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use termcolor::{Color, ColorSpec};
+use typst_syntax::Tag;
+
+use crate::Error;
+
+/// The style of a piece of highlighted text.
+///
+/// ```
+/// # use typst_ansi_hl::{ext::termcolor::Color, Style};
+/// const HEADING: Style = Style::new().fg(Color::Cyan).bold();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Style {
+    /// The foreground color.
+    #[serde(with = "color", skip_serializing_if = "Option::is_none")]
+    pub fg: Option<Color>,
+    /// The background color.
+    #[serde(with = "color", skip_serializing_if = "Option::is_none")]
+    pub bg: Option<Color>,
+    /// Whether the text is bold.
+    #[serde(skip_serializing_if = "is_false")]
+    pub bold: bool,
+    /// Whether the text is italic.
+    #[serde(skip_serializing_if = "is_false")]
+    pub italic: bool,
+    /// Whether the text is underlined.
+    #[serde(skip_serializing_if = "is_false")]
+    pub underline: bool,
+    /// Whether the text is dimmed.
+    #[serde(rename = "dim", skip_serializing_if = "is_false")]
+    pub dimmed: bool,
+}
+
+impl Style {
+    /// A style without any colors or effects.
+    pub const fn new() -> Style {
+        Style {
+            fg: None,
+            bg: None,
+            bold: false,
+            italic: false,
+            underline: false,
+            dimmed: false,
+        }
+    }
+
+    /// Set the foreground color.
+    pub const fn fg(mut self, color: Color) -> Style {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Set the background color.
+    pub const fn bg(mut self, color: Color) -> Style {
+        self.bg = Some(color);
+        self
+    }
+
+    /// Make the text bold.
+    pub const fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    /// Make the text italic.
+    pub const fn italic(mut self) -> Style {
+        self.italic = true;
+        self
+    }
+
+    /// Underline the text.
+    pub const fn underline(mut self) -> Style {
+        self.underline = true;
+        self
+    }
+
+    /// Dim the text.
+    pub const fn dimmed(mut self) -> Style {
+        self.dimmed = true;
+        self
+    }
+
+    /// Whether this style neither sets colors nor effects.
+    pub fn is_plain(&self) -> bool {
+        *self == Style::new()
+    }
+}
+
+/// Parses a comma-separated list of style attributes, such as `magenta,bold`.
+///
+/// The attributes are `bold`, `italic`, `underline` and `dim`.
+/// A color on its own or prefixed with `fg:` sets the foreground color,
+/// and a color prefixed with `bg:` sets the background color.
+/// `none` stands for a style without any colors or effects.
+impl FromStr for Style {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut style = Style::new();
+        for attr in s.split(',').map(str::trim) {
+            match attr {
+                "none" => {}
+                "bold" => style.bold = true,
+                "italic" => style.italic = true,
+                "underline" => style.underline = true,
+                "dim" => style.dimmed = true,
+                _ => {
+                    let (slot, color) = match attr.split_once(':') {
+                        Some(("fg", color)) => (&mut style.fg, color),
+                        Some(("bg", color)) => (&mut style.bg, color),
+                        _ => (&mut style.fg, attr),
+                    };
+                    *slot = Some(
+                        parse_color(color).ok_or_else(|| Error::InvalidStyle(attr.to_owned()))?,
+                    );
+                }
+            }
+        }
+        Ok(style)
+    }
+}
+
+impl From<Style> for ColorSpec {
+    fn from(style: Style) -> Self {
+        let mut spec = ColorSpec::new();
+        spec.set_fg(style.fg)
+            .set_bg(style.bg)
+            .set_bold(style.bold)
+            .set_italic(style.italic)
+            .set_underline(style.underline)
+            .set_dimmed(style.dimmed);
+        spec
+    }
+}
+
+/// A mapping from each highlighting [`Tag`] to a [`Style`].
+///
+/// Themes can be loaded from TOML or JSON files, where each tag is a key.
+/// Tags that are not mentioned keep their style from [`Theme::DEFAULT`].
+///
+/// ```toml
+/// [heading]
+/// fg = "magenta"
+/// bold = true
+///
+/// [comment]
+/// fg = "#808080"
+/// italic = true
+/// ```
+///
+/// Colors are either one of the eight basic color names, optionally prefixed
+/// with `bright-`, an index into the 256-color palette, or a `#rrggbb` hex code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Theme {
+    pub comment: Style,
+    pub punctuation: Style,
+    pub escape: Style,
+    pub strong: Style,
+    pub emph: Style,
+    pub link: Style,
+    pub raw: Style,
+    pub label: Style,
+    #[serde(rename = "ref")]
+    pub reference: Style,
+    pub heading: Style,
+    pub list_marker: Style,
+    pub list_term: Style,
+    pub math_delimiter: Style,
+    pub math_operator: Style,
+    pub keyword: Style,
+    pub operator: Style,
+    pub number: Style,
+    pub string: Style,
+    pub function: Style,
+    pub interpolated: Style,
+    pub error: Style,
+}
+
+impl Theme {
+    /// The default theme, which only uses the eight basic colors.
+    pub const DEFAULT: Theme = Theme {
+        comment: Style::new().dimmed(),
+        punctuation: Style::new(),
+        escape: Style::new().fg(Color::Cyan),
+        strong: Style::new().fg(Color::Yellow).bold(),
+        emph: Style::new().fg(Color::Yellow).italic(),
+        link: Style::new().fg(Color::Blue).underline(),
+        raw: Style::new().fg(Color::White),
+        label: Style::new().fg(Color::Blue).underline(),
+        reference: Style::new().fg(Color::Blue).underline(),
+        heading: Style::new().fg(Color::Cyan).bold(),
+        list_marker: Style::new().fg(Color::Cyan),
+        list_term: Style::new().fg(Color::Cyan),
+        math_delimiter: Style::new().fg(Color::Cyan),
+        math_operator: Style::new().fg(Color::Cyan),
+        keyword: Style::new().fg(Color::Magenta),
+        operator: Style::new().fg(Color::Cyan),
+        number: Style::new().fg(Color::Yellow),
+        string: Style::new().fg(Color::Green),
+        function: Style::new().fg(Color::Blue).italic(),
+        interpolated: Style::new().fg(Color::White),
+        error: Style::new().fg(Color::Red),
+    };
+
+    /// A theme using bright colors and no dimmed text.
+    pub const HIGH_CONTRAST: Theme = Theme {
+        comment: Style::new().fg(Color::White).italic(),
+        punctuation: Style::new(),
+        escape: Style::new().fg(BRIGHT_CYAN).bold(),
+        strong: Style::new().fg(BRIGHT_YELLOW).bold(),
+        emph: Style::new().fg(BRIGHT_YELLOW).italic(),
+        link: Style::new().fg(BRIGHT_BLUE).underline(),
+        raw: Style::new().fg(BRIGHT_WHITE),
+        label: Style::new().fg(BRIGHT_BLUE).underline(),
+        reference: Style::new().fg(BRIGHT_BLUE).underline(),
+        heading: Style::new().fg(BRIGHT_CYAN).bold().underline(),
+        list_marker: Style::new().fg(BRIGHT_CYAN).bold(),
+        list_term: Style::new().fg(BRIGHT_CYAN).bold(),
+        math_delimiter: Style::new().fg(BRIGHT_CYAN),
+        math_operator: Style::new().fg(BRIGHT_CYAN),
+        keyword: Style::new().fg(BRIGHT_MAGENTA).bold(),
+        operator: Style::new().fg(BRIGHT_CYAN),
+        number: Style::new().fg(BRIGHT_YELLOW),
+        string: Style::new().fg(BRIGHT_GREEN),
+        function: Style::new().fg(BRIGHT_BLUE).italic(),
+        interpolated: Style::new().fg(BRIGHT_WHITE),
+        error: Style::new().fg(BRIGHT_WHITE).bg(Color::Red).bold(),
+    };
+
+    /// A theme using the accent colors of the Solarized palette.
+    pub const SOLARIZED: Theme = Theme {
+        comment: Style::new().fg(Color::Rgb(0x58, 0x6e, 0x75)).italic(),
+        punctuation: Style::new(),
+        escape: Style::new().fg(Color::Rgb(0xcb, 0x4b, 0x16)),
+        strong: Style::new().fg(Color::Rgb(0xb5, 0x89, 0x00)).bold(),
+        emph: Style::new().fg(Color::Rgb(0xb5, 0x89, 0x00)).italic(),
+        link: Style::new().fg(Color::Rgb(0x26, 0x8b, 0xd2)).underline(),
+        raw: Style::new().fg(Color::Rgb(0x93, 0xa1, 0xa1)),
+        label: Style::new().fg(Color::Rgb(0x6c, 0x71, 0xc4)).underline(),
+        reference: Style::new().fg(Color::Rgb(0x6c, 0x71, 0xc4)).underline(),
+        heading: Style::new().fg(Color::Rgb(0x26, 0x8b, 0xd2)).bold(),
+        list_marker: Style::new().fg(Color::Rgb(0x2a, 0xa1, 0x98)),
+        list_term: Style::new().fg(Color::Rgb(0x2a, 0xa1, 0x98)),
+        math_delimiter: Style::new().fg(Color::Rgb(0x2a, 0xa1, 0x98)),
+        math_operator: Style::new().fg(Color::Rgb(0x2a, 0xa1, 0x98)),
+        keyword: Style::new().fg(Color::Rgb(0x85, 0x99, 0x00)),
+        operator: Style::new().fg(Color::Rgb(0x2a, 0xa1, 0x98)),
+        number: Style::new().fg(Color::Rgb(0xd3, 0x36, 0x82)),
+        string: Style::new().fg(Color::Rgb(0x2a, 0xa1, 0x98)),
+        function: Style::new().fg(Color::Rgb(0x26, 0x8b, 0xd2)).italic(),
+        interpolated: Style::new().fg(Color::Rgb(0x93, 0xa1, 0xa1)),
+        error: Style::new().fg(Color::Rgb(0xdc, 0x32, 0x2f)),
+    };
+
+    /// A theme for terminals with a light background.
+    ///
+    /// It avoids yellow and white text as well as dimmed comments,
+    /// which are hard to read on a light background.
+    pub const LIGHT: Theme = Theme {
+        comment: Style::new().fg(BRIGHT_BLACK).italic(),
+        punctuation: Style::new(),
+        escape: Style::new().fg(Color::Cyan),
+        strong: Style::new().fg(Color::Red).bold(),
+        emph: Style::new().fg(Color::Red).italic(),
+        link: Style::new().fg(Color::Blue).underline(),
+        raw: Style::new().fg(Color::Black),
+        label: Style::new().fg(Color::Blue).underline(),
+        reference: Style::new().fg(Color::Blue).underline(),
+        heading: Style::new().fg(Color::Blue).bold(),
+        list_marker: Style::new().fg(Color::Cyan),
+        list_term: Style::new().fg(Color::Cyan),
+        math_delimiter: Style::new().fg(Color::Cyan),
+        math_operator: Style::new().fg(Color::Cyan),
+        keyword: Style::new().fg(Color::Magenta),
+        operator: Style::new().fg(Color::Cyan),
+        number: Style::new().fg(Color::Red),
+        string: Style::new().fg(Color::Green),
+        function: Style::new().fg(Color::Blue).italic(),
+        interpolated: Style::new().fg(Color::Black),
+        error: Style::new().fg(Color::Red).underline(),
+    };
+
+    /// A theme tuned for Discord's ANSI code blocks.
+    ///
+    /// Discord only renders the eight basic colors, bold and underline.
+    /// Black is displayed as gray, which is used for comments.
+    pub const DISCORD: Theme = Theme {
+        comment: Style::new().fg(Color::Black),
+        punctuation: Style::new(),
+        escape: Style::new().fg(Color::Cyan),
+        strong: Style::new().fg(Color::Yellow).bold(),
+        emph: Style::new().fg(Color::Yellow),
+        link: Style::new().fg(Color::Blue).underline(),
+        raw: Style::new().fg(Color::White),
+        label: Style::new().fg(Color::Blue).underline(),
+        reference: Style::new().fg(Color::Blue).underline(),
+        heading: Style::new().fg(Color::Cyan).bold(),
+        list_marker: Style::new().fg(Color::Cyan),
+        list_term: Style::new().fg(Color::Cyan),
+        math_delimiter: Style::new().fg(Color::Cyan),
+        math_operator: Style::new().fg(Color::Cyan),
+        keyword: Style::new().fg(Color::Magenta),
+        operator: Style::new().fg(Color::Cyan),
+        number: Style::new().fg(Color::Yellow),
+        string: Style::new().fg(Color::Green),
+        function: Style::new().fg(Color::Blue),
+        interpolated: Style::new().fg(Color::White),
+        error: Style::new().fg(Color::Red).underline(),
+    };
+
+    /// All built-in themes together with their names.
+    pub const BUILTIN: &'static [(&'static str, Theme)] = &[
+        ("default", Theme::DEFAULT),
+        ("high-contrast", Theme::HIGH_CONTRAST),
+        ("solarized", Theme::SOLARIZED),
+        ("light", Theme::LIGHT),
+        ("discord", Theme::DISCORD),
+    ];
+
+    /// Look up a built-in theme by its name.
+    pub fn builtin(name: &str) -> Option<Theme> {
+        Theme::BUILTIN
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|&(_, theme)| theme)
+    }
+
+    /// The name of a tag as used in theme files, such as `list-marker`.
+    pub fn tag_name(tag: Tag) -> &'static str {
+        TAG_NAMES[tag as usize]
+    }
+
+    /// Look up a tag by the name used in theme files.
+    pub fn tag_by_name(name: &str) -> Option<Tag> {
+        let index = TAG_NAMES.iter().position(|&n| n == name)?;
+        Some(Tag::LIST[index])
+    }
+
+    /// Parse a theme from a TOML string.
+    pub fn from_toml(input: &str) -> Result<Theme, Error> {
+        Ok(toml::from_str(input)?)
+    }
+
+    /// Parse a theme from a JSON string.
+    pub fn from_json(input: &str) -> Result<Theme, Error> {
+        Ok(serde_json::from_str(input)?)
+    }
+
+    /// Load a theme from a `.toml` or `.json` file.
+    pub fn load(path: &Path) -> Result<Theme, Error> {
+        let input = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Theme::from_toml(&input),
+            Some("json") => Theme::from_json(&input),
+            _ => Err(Error::UnknownThemeFormat(path.to_owned())),
+        }
+    }
+
+    /// The style used for the given tag.
+    pub fn get(&self, tag: Tag) -> &Style {
+        match tag {
+            Tag::Comment => &self.comment,
+            Tag::Punctuation => &self.punctuation,
+            Tag::Escape => &self.escape,
+            Tag::Strong => &self.strong,
+            Tag::Emph => &self.emph,
+            Tag::Link => &self.link,
+            Tag::Raw => &self.raw,
+            Tag::Label => &self.label,
+            Tag::Ref => &self.reference,
+            Tag::Heading => &self.heading,
+            Tag::ListMarker => &self.list_marker,
+            Tag::ListTerm => &self.list_term,
+            Tag::MathDelimiter => &self.math_delimiter,
+            Tag::MathOperator => &self.math_operator,
+            Tag::Keyword => &self.keyword,
+            Tag::Operator => &self.operator,
+            Tag::Number => &self.number,
+            Tag::String => &self.string,
+            Tag::Function => &self.function,
+            Tag::Interpolated => &self.interpolated,
+            Tag::Error => &self.error,
+        }
+    }
+
+    /// A mutable reference to the style used for the given tag.
+    pub fn get_mut(&mut self, tag: Tag) -> &mut Style {
+        match tag {
+            Tag::Comment => &mut self.comment,
+            Tag::Punctuation => &mut self.punctuation,
+            Tag::Escape => &mut self.escape,
+            Tag::Strong => &mut self.strong,
+            Tag::Emph => &mut self.emph,
+            Tag::Link => &mut self.link,
+            Tag::Raw => &mut self.raw,
+            Tag::Label => &mut self.label,
+            Tag::Ref => &mut self.reference,
+            Tag::Heading => &mut self.heading,
+            Tag::ListMarker => &mut self.list_marker,
+            Tag::ListTerm => &mut self.list_term,
+            Tag::MathDelimiter => &mut self.math_delimiter,
+            Tag::MathOperator => &mut self.math_operator,
+            Tag::Keyword => &mut self.keyword,
+            Tag::Operator => &mut self.operator,
+            Tag::Number => &mut self.number,
+            Tag::String => &mut self.string,
+            Tag::Function => &mut self.function,
+            Tag::Interpolated => &mut self.interpolated,
+            Tag::Error => &mut self.error,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::DEFAULT
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// The names of the tags in the order of [`Tag::LIST`].
+const TAG_NAMES: [&str; 21] = [
+    "comment",
+    "punctuation",
+    "escape",
+    "strong",
+    "emph",
+    "link",
+    "raw",
+    "label",
+    "ref",
+    "heading",
+    "list-marker",
+    "list-term",
+    "math-delimiter",
+    "math-operator",
+    "keyword",
+    "operator",
+    "number",
+    "string",
+    "function",
+    "interpolated",
+    "error",
+];
+
+const BRIGHT_BLACK: Color = Color::Ansi256(8);
+const BRIGHT_GREEN: Color = Color::Ansi256(10);
+const BRIGHT_YELLOW: Color = Color::Ansi256(11);
+const BRIGHT_BLUE: Color = Color::Ansi256(12);
+const BRIGHT_MAGENTA: Color = Color::Ansi256(13);
+const BRIGHT_CYAN: Color = Color::Ansi256(14);
+const BRIGHT_WHITE: Color = Color::Ansi256(15);
+
+const COLOR_NAMES: [(&str, Color); 8] = [
+    ("black", Color::Black),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("yellow", Color::Yellow),
+    ("blue", Color::Blue),
+    ("magenta", Color::Magenta),
+    ("cyan", Color::Cyan),
+    ("white", Color::White),
+];
+
+/// Parse a color as used in themes and styles.
+///
+/// This is one of the eight basic color names, optionally prefixed with `bright-`,
+/// an index into the 256-color palette, or a `#rrggbb` hex code.
+///
+/// ```
+/// # use typst_ansi_hl::{ext::termcolor::Color, parse_color};
+/// assert_eq!(parse_color("bright-red"), Some(Color::Ansi256(9)));
+/// assert_eq!(parse_color("#ff8000"), Some(Color::Rgb(0xff, 0x80, 0x00)));
+/// ```
+pub fn parse_color(input: &str) -> Option<Color> {
+    if let Some(hex) = input.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    if let Ok(index) = input.parse::<u8>() {
+        return Some(Color::Ansi256(index));
+    }
+
+    let (name, bright) = match input.strip_prefix("bright-") {
+        Some(name) => (name, true),
+        None => (input, false),
+    };
+    let index = COLOR_NAMES.iter().position(|&(n, _)| n == name)?;
+    Some(match bright {
+        true => Color::Ansi256(index as u8 + 8),
+        false => COLOR_NAMES[index].1,
+    })
+}
+
+/// (De)serialization of optional colors in the format accepted by [`parse_color`].
+mod color {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+    use termcolor::Color;
+
+    use super::COLOR_NAMES;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Index(u8),
+        Name(String),
+    }
+
+    pub fn serialize<S: Serializer>(
+        color: &Option<Color>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match color {
+            None => serializer.serialize_none(),
+            Some(Color::Ansi256(index)) => serializer.serialize_u8(*index),
+            Some(Color::Rgb(r, g, b)) => {
+                serializer.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}"))
+            }
+            Some(color) => match COLOR_NAMES.iter().find(|(_, c)| c == color) {
+                Some((name, _)) => serializer.serialize_str(name),
+                None => serializer.serialize_none(),
+            },
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Color>, D::Error> {
+        match Repr::deserialize(deserializer)? {
+            Repr::Index(index) => Ok(Some(Color::Ansi256(index))),
+            Repr::Name(name) => super::parse_color(&name)
+                .map(Some)
+                .ok_or_else(|| D::Error::custom(format!("invalid color `{name}`"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("red"), Some(Color::Red));
+        assert_eq!(parse_color("bright-red"), Some(Color::Ansi256(9)));
+        assert_eq!(parse_color("42"), Some(Color::Ansi256(42)));
+        assert_eq!(parse_color("#ff8000"), Some(Color::Rgb(0xff, 0x80, 0x00)));
+        assert_eq!(parse_color("#ff80"), None);
+        assert_eq!(parse_color("purple"), None);
+    }
+
+    #[test]
+    fn test_parse_style() {
+        assert_eq!(
+            "magenta,bold".parse::<Style>().unwrap(),
+            Style::new().fg(Color::Magenta).bold()
+        );
+        assert_eq!(
+            "dim, bg:#000080".parse::<Style>().unwrap(),
+            Style::new().bg(Color::Rgb(0, 0, 0x80)).dimmed()
+        );
+        assert_eq!("none".parse::<Style>().unwrap(), Style::new());
+        assert!("bold,blink".parse::<Style>().is_err());
+    }
+
+    #[test]
+    fn test_builtin_themes() {
+        let expected = [
+            ("default", Tag::Comment, Style::new().dimmed()),
+            (
+                "high-contrast",
+                Tag::Heading,
+                Style::new().fg(BRIGHT_CYAN).bold().underline(),
+            ),
+            (
+                "solarized",
+                Tag::Keyword,
+                Style::new().fg(Color::Rgb(0x85, 0x99, 0x00)),
+            ),
+            (
+                "light",
+                Tag::Comment,
+                Style::new().fg(BRIGHT_BLACK).italic(),
+            ),
+            ("discord", Tag::Comment, Style::new().fg(Color::Black)),
+        ];
+        assert_eq!(Theme::BUILTIN.len(), expected.len());
+        for ((name, theme), (expected_name, tag, style)) in Theme::BUILTIN.iter().zip(expected) {
+            assert_eq!(*name, expected_name);
+            assert_eq!(Theme::builtin(name), Some(*theme));
+            assert_eq!(*theme.get(tag), style, "{name}");
+        }
+        assert_eq!(Theme::builtin("dark"), None);
+    }
+
+    #[test]
+    fn test_tag_names() {
+        for &tag in Tag::LIST {
+            assert_eq!(Theme::tag_by_name(Theme::tag_name(tag)), Some(tag));
+        }
+    }
+
+    #[test]
+    fn test_load_theme() {
+        let theme =
+            Theme::from_toml("[heading]\nfg = \"red\"\nitalic = true\n[ref]\nbg = 3").unwrap();
+        assert_eq!(theme.heading, Style::new().fg(Color::Red).italic());
+        assert_eq!(theme.reference, Style::new().bg(Color::Ansi256(3)));
+        assert_eq!(theme.comment, Theme::DEFAULT.comment);
+
+        let json = serde_json::to_string(&Theme::DEFAULT).unwrap();
+        assert_eq!(Theme::from_json(&json).unwrap(), Theme::DEFAULT);
+    }
+}
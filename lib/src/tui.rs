@@ -0,0 +1,139 @@
+//! Converting highlighted code into text for [`ratatui`].
+use std::io;
+
+use ratatui::{
+    style::{Color as TuiColor, Modifier, Style as TuiStyle},
+    text::{Line, Span, Text},
+};
+use termcolor::Color;
+use typst_syntax::Tag;
+
+use crate::{render::Renderer, ColorDepth, Style};
+
+/// Collects styled text as the lines and spans of a ratatui [`Text`].
+///
+/// All colors are converted to the given color depth.
+/// Links are rendered as plain text.
+pub struct RatatuiRenderer {
+    color_depth: ColorDepth,
+    lines: Vec<Line<'static>>,
+    style: TuiStyle,
+}
+
+impl RatatuiRenderer {
+    /// Create a renderer with the given color depth.
+    pub fn new(color_depth: ColorDepth) -> RatatuiRenderer {
+        RatatuiRenderer {
+            color_depth,
+            lines: vec![Line::default()],
+            style: TuiStyle::new(),
+        }
+    }
+
+    /// Return the collected text.
+    pub fn into_text(mut self) -> Text<'static> {
+        // A trailing newline doesn't start another line.
+        if self.lines.len() > 1 && self.lines.last().is_some_and(|line| line.spans.is_empty()) {
+            self.lines.pop();
+        }
+        Text::from(self.lines)
+    }
+
+    fn color(&self, color: Color) -> TuiColor {
+        match self.color_depth.quantize(color) {
+            Color::Black => TuiColor::Black,
+            Color::Red => TuiColor::Red,
+            Color::Green => TuiColor::Green,
+            Color::Yellow => TuiColor::Yellow,
+            Color::Blue => TuiColor::Blue,
+            Color::Magenta => TuiColor::Magenta,
+            Color::Cyan => TuiColor::Cyan,
+            // ratatui calls the bright variant of white `White`.
+            Color::White => TuiColor::Gray,
+            Color::Ansi256(index) => TuiColor::Indexed(index),
+            Color::Rgb(r, g, b) => TuiColor::Rgb(r, g, b),
+            _ => TuiColor::Reset,
+        }
+    }
+
+    fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let line = self.lines.last_mut().expect("there is always a line");
+        line.spans.push(Span::styled(text.to_owned(), self.style));
+    }
+}
+
+impl Renderer for RatatuiRenderer {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        let mut tui_style = TuiStyle::new();
+        if let Some(fg) = style.fg {
+            tui_style = tui_style.fg(self.color(fg));
+        }
+        if let Some(bg) = style.bg {
+            tui_style = tui_style.bg(self.color(bg));
+        }
+        for (enabled, modifier) in [
+            (style.bold, Modifier::BOLD),
+            (style.italic, Modifier::ITALIC),
+            (style.underline, Modifier::UNDERLINED),
+            (style.dimmed, Modifier::DIM),
+        ] {
+            if enabled {
+                tui_style = tui_style.add_modifier(modifier);
+            }
+        }
+        self.style = tui_style;
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        self.style = TuiStyle::new();
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        let mut lines = text.split('\n');
+        if let Some(first) = lines.next() {
+            self.push(first.trim_end_matches('\r'));
+        }
+        for line in lines {
+            self.lines.push(Line::default());
+            self.push(line.trim_end_matches('\r'));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{
+        backend::TestBackend,
+        buffer::Buffer,
+        layout::Rect,
+        style::{Color, Style, Stylize},
+        widgets::Paragraph,
+        Terminal,
+    };
+
+    use crate::Highlighter;
+
+    #[test]
+    fn test_highlight_ratatui() {
+        let text = Highlighter::default()
+            .highlight_ratatui("= Hi\n#let x\n")
+            .unwrap();
+        assert_eq!(text.lines.len(), 2);
+
+        let mut terminal = Terminal::new(TestBackend::new(6, 2)).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(Paragraph::new(text), frame.area()))
+            .unwrap();
+
+        let mut expected = Buffer::with_lines(["= Hi", "#let x"]);
+        expected.set_style(Rect::new(0, 0, 1, 1), Style::new().cyan().bold());
+        expected.set_style(Rect::new(0, 1, 4, 1), Style::new().fg(Color::Magenta));
+        terminal.backend().assert_buffer(&expected);
+    }
+}
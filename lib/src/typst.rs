@@ -0,0 +1,139 @@
+//! Rendering highlighted code as Typst markup.
+use std::io;
+
+use termcolor::Color;
+use typst_syntax::Tag;
+
+use crate::{
+    color::{hex, HexFormat},
+    render::Renderer,
+    ColorDepth, Style,
+};
+
+/// Renders styles as `#text`, `#highlight` and `#underline` calls and links as `#link` calls.
+///
+/// Every character that could have a meaning in markup is escaped, and spaces that would
+/// be collapsed are written as `\u{20}`, so the output shows exactly the input.
+/// All colors are converted to the given color depth.
+///
+/// Unlike [`Highlighter::highlight_typst`], this doesn't wrap the output in `#block`.
+///
+/// [`Highlighter::highlight_typst`]: crate::Highlighter::highlight_typst
+pub struct TypstRenderer<W> {
+    inner: W,
+    color_depth: ColorDepth,
+    closing: usize,
+    /// Whether the last character written was part of a line and not whitespace.
+    after_text: bool,
+}
+
+impl<W: io::Write> TypstRenderer<W> {
+    /// Create a renderer writing to the given writer.
+    pub fn new(writer: W, color_depth: ColorDepth) -> TypstRenderer<W> {
+        TypstRenderer {
+            inner: writer,
+            color_depth,
+            closing: 0,
+            after_text: false,
+        }
+    }
+
+    /// Return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn rgb(&self, color: Color, dimmed: bool) -> String {
+        let hex = hex(self.color_depth.quantize(color), HexFormat::Css);
+        // Dimmed text is drawn at 60% opacity.
+        let alpha = if dimmed { "99" } else { "" };
+        format!("rgb(\"{hex}{alpha}\")")
+    }
+}
+
+impl<W: io::Write> Renderer for TypstRenderer<W> {
+    fn start_style(&mut self, style: &Style, _: Option<Tag>) -> io::Result<()> {
+        if let Some(bg) = style.bg {
+            write!(self.inner, "#highlight(fill: {})[", self.rgb(bg, false))?;
+            self.closing += 1;
+        }
+
+        let mut args = Vec::new();
+        match (style.fg, style.dimmed) {
+            (Some(fg), dimmed) => args.push(format!("fill: {}", self.rgb(fg, dimmed))),
+            (None, true) => args.push(format!("fill: {}", self.rgb(Color::Black, true))),
+            (None, false) => {}
+        }
+        if style.bold {
+            args.push("weight: \"bold\"".to_owned());
+        }
+        if style.italic {
+            args.push("style: \"italic\"".to_owned());
+        }
+        if !args.is_empty() {
+            write!(self.inner, "#text({})[", args.join(", "))?;
+            self.closing += 1;
+        }
+
+        if style.underline {
+            write!(self.inner, "#underline[")?;
+            self.closing += 1;
+        }
+        Ok(())
+    }
+
+    fn end_style(&mut self) -> io::Result<()> {
+        write!(self.inner, "{}", "]".repeat(self.closing))?;
+        self.closing = 0;
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) -> io::Result<()> {
+        for c in text.chars() {
+            match c {
+                // A backslash at the end of a line is a line break.
+                '\n' => writeln!(self.inner, "\\")?,
+                '\r' => continue,
+                ' ' if self.after_text => write!(self.inner, " ")?,
+                c if c.is_ascii_punctuation() => write!(self.inner, "\\{c}")?,
+                c if c.is_whitespace() || c.is_control() => {
+                    write!(self.inner, "\\u{{{:x}}}", u32::from(c))?
+                }
+                c => write!(self.inner, "{c}")?,
+            }
+            self.after_text = !c.is_whitespace();
+        }
+        Ok(())
+    }
+
+    fn start_link(&mut self, url: &str) -> io::Result<()> {
+        let url = url.replace('\\', "\\\\").replace('"', "\\\"");
+        write!(self.inner, "#link(\"{url}\")[")
+    }
+
+    fn end_link(&mut self) -> io::Result<()> {
+        write!(self.inner, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Highlighter;
+
+    #[test]
+    fn test_highlight_typst() {
+        let input = "= A\n  #let x = \"a\\\\b\"  // [*]\n\n$x^2$ https://typst.app";
+        let output = Highlighter::default().highlight_typst(input).unwrap();
+        assert!(
+            output.starts_with("#block(breakable: true)[#set text(font: \"DejaVu Sans Mono\");")
+        );
+        assert!(output.contains(
+            "#text(fill: rgb(\"#00cdcd\"), weight: \"bold\")[\\=] A\\\n\
+             \\u{20}\\u{20}#text(fill: rgb(\"#cd00cd\"))[\\#let]"
+        ));
+        assert!(output.contains("#link(\"https://typst.app\")["));
+
+        // The output has to be valid markup.
+        assert!(!typst_syntax::parse(&output).erroneous());
+    }
+}
@@ -0,0 +1,102 @@
+//! Expressing emphasis with the styled letters of Unicode's Mathematical Alphanumeric Symbols.
+use typst_syntax::{LinkedNode, SyntaxKind};
+
+use crate::HighlightLevel;
+
+/// A style of letters in the Mathematical Alphanumeric Symbols block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Letters {
+    Plain,
+    Bold,
+    Italic,
+    BoldItalic,
+    Monospace,
+}
+
+impl Letters {
+    /// The style of letters inside the given node.
+    ///
+    /// Like the other formats, raw text is styled from [`HighlightLevel::WithRaw`] on,
+    /// and strong and emphasized text from [`HighlightLevel::WithStyles`] on.
+    fn within(self, node: &LinkedNode, hl_level: HighlightLevel) -> Letters {
+        match (self, node.kind()) {
+            // Only the code is monospace, not the backticks and the language tag around it.
+            (_, SyntaxKind::Raw) => Letters::Plain,
+            (_, SyntaxKind::Text | SyntaxKind::RawTrimmed)
+                if node.parent_kind() == Some(SyntaxKind::Raw)
+                    && hl_level >= HighlightLevel::WithRaw =>
+            {
+                Letters::Monospace
+            }
+            (letters, _) if hl_level < HighlightLevel::WithStyles => letters,
+            (Letters::Plain, SyntaxKind::Strong | SyntaxKind::Heading) => Letters::Bold,
+            (Letters::Italic, SyntaxKind::Strong | SyntaxKind::Heading) => Letters::BoldItalic,
+            (Letters::Plain, SyntaxKind::Emph) => Letters::Italic,
+            (Letters::Bold, SyntaxKind::Emph) => Letters::BoldItalic,
+            (letters, _) => letters,
+        }
+    }
+
+    /// The styled counterpart of a character, or the character itself if there is none.
+    fn apply(self, c: char) -> char {
+        // The code points of `A`, `a` and `0` in each style.
+        let (upper, lower, digit) = match self {
+            Letters::Plain => return c,
+            Letters::Bold => (0x1D400, 0x1D41A, Some(0x1D7CE)),
+            Letters::Italic => (0x1D434, 0x1D44E, None),
+            Letters::BoldItalic => (0x1D468, 0x1D482, Some(0x1D7CE)),
+            Letters::Monospace => (0x1D670, 0x1D68A, Some(0x1D7F6)),
+        };
+        let code = match c {
+            // The italic h was already part of the Letterlike Symbols block.
+            'h' if self == Letters::Italic => return '\u{210E}',
+            'A'..='Z' => upper + (c as u32 - 'A' as u32),
+            'a'..='z' => lower + (c as u32 - 'a' as u32),
+            '0'..='9' => match digit {
+                Some(digit) => digit + (c as u32 - '0' as u32),
+                None => return c,
+            },
+            _ => return c,
+        };
+        char::from_u32(code).unwrap_or(c)
+    }
+}
+
+/// Write the text of a node with styled letters for strong, emphasized and raw text
+/// and headings.
+pub(crate) fn styled_letters(
+    node: &LinkedNode,
+    letters: Letters,
+    hl_level: HighlightLevel,
+    out: &mut String,
+) {
+    let letters = letters.within(node, hl_level);
+    if node.text().is_empty() {
+        for child in node.children() {
+            styled_letters(&child, letters, hl_level, out);
+        }
+    } else {
+        out.extend(node.text().chars().map(|c| letters.apply(c)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Highlighter;
+
+    #[test]
+    fn test_highlight_unicode() {
+        let output = Highlighter::default()
+            .highlight_unicode("= Hi 2\n*bold _hi_* `x1` ä")
+            .unwrap();
+        assert_eq!(output, "= 𝐇𝐢 𝟐\n*𝐛𝐨𝐥𝐝 _𝒉𝒊_* `𝚡𝟷` ä");
+
+        let output = Highlighter::default().highlight_unicode("_h1_").unwrap();
+        assert_eq!(output, "_ℎ1_");
+
+        let output = Highlighter::default()
+            .highlight_unicode("```rust\nlet x = 1;\n```")
+            .unwrap();
+        assert_eq!(output, "```rust\n𝚕𝚎𝚝 𝚡 = 𝟷;\n```");
+    }
+}
@@ -1,8 +1,14 @@
-use std::{io::Read, path::PathBuf};
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
 
 use clap::{ArgAction, Parser, ValueEnum};
 use color_eyre::eyre::{Context as _, Result};
-use typst_ansi_hl::Highlighter;
+use typst_ansi_hl::{
+    ext::{termcolor::Color, two_face::theme::EmbeddedLazyThemeSet, typst_syntax::Tag},
+    Highlighter, HtmlStyle, RawTheme, Style, SvgOptions, Theme,
+};
 
 #[derive(clap::Parser)]
 struct Args {
@@ -19,6 +25,12 @@ struct Args {
     #[doc(hidden)]
     _no_discord: bool,
 
+    /// Print the content of a Matrix message as JSON, with the highlighted code as `formatted_body`.
+    ///
+    /// The soft limit applies to the whole JSON object.
+    #[clap(long, conflicts_with_all = ["discord", "format"])]
+    matrix: bool,
+
     // Logically this comes after `Args::strip_ansi`, but in clap it makes more sense before.
     // Also see https://jwodder.github.io/kbits/posts/clap-bool-negate/
     /// Strip all ANSI escape sequences from the input before processing. [default]
@@ -66,6 +78,95 @@ struct Args {
     /// The kind of input syntax.
     #[clap(short, long, default_value = "markup")]
     mode: SyntaxMode,
+
+    /// The name of a built-in theme, or a `.toml` or `.json` file mapping each kind of token to a style.
+    #[clap(short, long)]
+    theme: Option<String>,
+
+    /// Replace the style of one kind of token, e.g. `heading=magenta,bold`.
+    ///
+    /// A style is a comma-separated list of `bold`, `italic`, `underline`, `dim`,
+    /// a foreground color, `bg:` followed by a background color, or `none`.
+    /// Colors are basic color names optionally prefixed with `bright-`,
+    /// 256-color palette indices, or `#rrggbb` hex codes.
+    #[clap(long = "style", value_name = "TAG=STYLE", value_parser = parse_style_override)]
+    styles: Vec<(Tag, Style)>,
+
+    /// Print the names of all built-in themes and exit.
+    #[clap(long)]
+    list_themes: bool,
+
+    /// The name of an embedded theme, or a `.tmTheme` file used for raw blocks with a language tag.
+    #[clap(long)]
+    raw_theme: Option<String>,
+
+    /// Print the names of all embedded themes for raw blocks and exit.
+    #[clap(long)]
+    list_raw_themes: bool,
+
+    /// Highlight raw blocks tagged with the language `ALIAS` using the syntax for `LANG`.
+    #[clap(long = "lang-alias", value_name = "ALIAS=LANG", value_parser = parse_lang_alias)]
+    lang_aliases: Vec<(String, String)>,
+
+    /// A directory of `.sublime-syntax` files used for raw blocks in addition to the default syntaxes.
+    #[clap(long)]
+    syntax_dir: Option<PathBuf>,
+
+    /// Guess the language of raw blocks without a language tag.
+    #[clap(long, overrides_with = "_no_guess_lang")]
+    guess_lang: bool,
+
+    /// Don't highlight raw blocks without a language tag. [default]
+    #[clap(long = "no-guess-lang")]
+    #[clap(hide_short_help = true)]
+    #[doc(hidden)]
+    _no_guess_lang: bool,
+
+    /// How confident a guess of `--guess-lang` must be, from 0 to 1.
+    #[clap(long, value_name = "CONFIDENCE", default_value = "0.5", value_parser = parse_confidence)]
+    guess_threshold: f32,
+
+    /// The output format.
+    #[clap(short, long, default_value = "ansi")]
+    format: Format,
+
+    /// In HTML output, use CSS classes like `typ-key` instead of inline styles.
+    ///
+    /// See `--html-stylesheet` for a stylesheet defining these classes.
+    #[clap(long)]
+    html_classes: bool,
+
+    /// Print a CSS stylesheet for the theme to be used with `--html-classes` and exit.
+    #[clap(long)]
+    html_stylesheet: bool,
+
+    /// In SVG output, show line numbers.
+    #[clap(long)]
+    svg_line_numbers: bool,
+
+    /// In SVG output, show a title bar with the given title. It may be empty.
+    #[clap(long, value_name = "TITLE")]
+    svg_title: Option<String>,
+
+    /// In SVG output, the color of the background.
+    #[clap(long, value_name = "COLOR", value_parser = parse_color)]
+    svg_background: Option<Color>,
+
+    /// In SVG output, the color of text without a color.
+    #[clap(long, value_name = "COLOR", value_parser = parse_color)]
+    svg_foreground: Option<Color>,
+
+    /// In BBCode output, the tag wrapping the code.
+    /// Forums have to read the formatting tags inside of it, which many don't do inside of `code`.
+    /// If empty, the code isn't wrapped.
+    #[clap(long, value_name = "TAG", default_value = "font=monospace")]
+    bbcode_wrapper: String,
+
+    /// How many colors the output may use.
+    ///
+    /// If set to `auto`, it is detected from the `COLORTERM` and `TERM` environment variables.
+    #[clap(long, default_value = "auto")]
+    color_depth: ColorDepth,
 }
 
 /// The kind of input syntax.
@@ -76,6 +177,127 @@ pub enum SyntaxMode {
     Math,
 }
 
+/// The output format.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// ANSI escape sequences.
+    Ansi,
+    /// HTML with a `<span>` per token.
+    Html,
+    /// A JSON object listing the position, kind and style of each token.
+    Json,
+    /// An SVG image.
+    Svg,
+    /// LaTeX using the xcolor and fancyvrb packages.
+    Latex,
+    /// A Rich Text Format document.
+    Rtf,
+    /// mIRC color codes for IRC.
+    Irc,
+    /// BBCode for forums.
+    Bbcode,
+    /// Pango markup for GTK and desktop notifications.
+    Pango,
+    /// Typst markup reproducing the highlighting.
+    Typst,
+    /// Plain text with bold, italic and monospace Unicode letters for strong, emphasized and raw text.
+    Unicode,
+}
+
+/// Parse a `TAG=STYLE` pair as given to `--style`.
+fn parse_style_override(input: &str) -> Result<(Tag, Style), String> {
+    let (name, style) = input
+        .split_once('=')
+        .ok_or("expected a token kind and a style separated by `=`")?;
+    let tag = Theme::tag_by_name(name.trim()).ok_or_else(|| {
+        let names: Vec<_> = Tag::LIST.iter().map(|&tag| Theme::tag_name(tag)).collect();
+        format!(
+            "unknown token kind `{name}`, expected one of: {}",
+            names.join(", ")
+        )
+    })?;
+    let style = style
+        .parse()
+        .map_err(|err: typst_ansi_hl::Error| err.to_string())?;
+    Ok((tag, style))
+}
+
+/// Parse a color as used in styles.
+fn parse_color(input: &str) -> Result<Color, String> {
+    typst_ansi_hl::parse_color(input).ok_or_else(|| format!("`{input}` is not a color"))
+}
+
+/// Parse a confidence between 0 and 1 as given to `--guess-threshold`.
+fn parse_confidence(input: &str) -> Result<f32, String> {
+    let confidence: f32 = input
+        .parse()
+        .map_err(|_| format!("`{input}` is not a number"))?;
+    if !(0.0..=1.0).contains(&confidence) {
+        return Err(format!("`{input}` is not between 0 and 1"));
+    }
+    Ok(confidence)
+}
+
+/// Parse an `ALIAS=LANG` pair as given to `--lang-alias`.
+fn parse_lang_alias(input: &str) -> Result<(String, String), String> {
+    let (alias, lang) = input
+        .split_once('=')
+        .ok_or("expected a language alias and a language separated by `=`")?;
+    Ok((alias.trim().to_owned(), lang.trim().to_owned()))
+}
+
+/// How many colors the output may use.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ColorDepth {
+    Auto,
+    #[value(name = "8")]
+    Ansi8,
+    #[value(name = "16")]
+    Ansi16,
+    #[value(name = "256")]
+    Ansi256,
+    #[value(name = "truecolor")]
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Resolve `auto` by looking at the environment.
+    fn resolve(self, discord: bool) -> typst_ansi_hl::ColorDepth {
+        match self {
+            // Discord only renders the eight basic colors.
+            ColorDepth::Auto if discord => typst_ansi_hl::ColorDepth::Ansi8,
+            ColorDepth::Auto => detect_color_depth(
+                &std::env::var("COLORTERM").unwrap_or_default(),
+                &std::env::var("TERM").unwrap_or_default(),
+            ),
+            ColorDepth::Ansi8 => typst_ansi_hl::ColorDepth::Ansi8,
+            ColorDepth::Ansi16 => typst_ansi_hl::ColorDepth::Ansi16,
+            ColorDepth::Ansi256 => typst_ansi_hl::ColorDepth::Ansi256,
+            ColorDepth::TrueColor => typst_ansi_hl::ColorDepth::TrueColor,
+        }
+    }
+}
+
+/// Detect the color depth of the terminal from the values of the `COLORTERM` and `TERM`
+/// environment variables.
+fn detect_color_depth(colorterm: &str, term: &str) -> typst_ansi_hl::ColorDepth {
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return typst_ansi_hl::ColorDepth::TrueColor;
+    }
+
+    if term.ends_with("-direct") {
+        typst_ansi_hl::ColorDepth::TrueColor
+    } else if term.contains("256color") {
+        typst_ansi_hl::ColorDepth::Ansi256
+    } else if term.ends_with("16color") || term == "linux" {
+        typst_ansi_hl::ColorDepth::Ansi16
+    } else if term.ends_with("8color") || term == "ansi" {
+        typst_ansi_hl::ColorDepth::Ansi8
+    } else {
+        typst_ansi_hl::ColorDepth::Ansi256
+    }
+}
+
 impl From<SyntaxMode> for typst_ansi_hl::SyntaxMode {
     fn from(value: SyntaxMode) -> Self {
         match value {
@@ -90,6 +312,79 @@ fn main() -> Result<()> {
     color_eyre::install()?;
 
     let args = Args::parse();
+    if args.list_themes {
+        for (name, _) in Theme::BUILTIN {
+            println!("{name}");
+        }
+        return Ok(());
+    }
+    if args.list_raw_themes {
+        for name in EmbeddedLazyThemeSet::theme_names() {
+            println!("{}", name.as_name());
+        }
+        return Ok(());
+    }
+
+    let mut highlighter = Highlighter::default();
+    if args.discord {
+        highlighter.for_discord();
+    }
+    highlighter.with_syntax_mode(args.mode.into());
+    highlighter.with_color_depth(args.color_depth.resolve(args.discord));
+    let mut theme = match &args.theme {
+        Some(theme) => match Theme::builtin(theme) {
+            Some(theme) => theme,
+            None => Theme::load(Path::new(theme)).wrap_err_with(|| {
+                format!("failed to load theme `{theme}` (see `--list-themes` for built-in themes)")
+            })?,
+        },
+        None => Theme::default(),
+    };
+    for &(tag, style) in &args.styles {
+        *theme.get_mut(tag) = style;
+    }
+    highlighter.with_theme(theme);
+    if args.html_stylesheet {
+        print!("{}", highlighter.html_stylesheet());
+        return Ok(());
+    }
+    let html_style = match args.html_classes {
+        true => HtmlStyle::Classes,
+        false => HtmlStyle::Inline,
+    };
+    let mut svg_options = SvgOptions {
+        line_numbers: args.svg_line_numbers,
+        title: args.svg_title.clone(),
+        ..SvgOptions::default()
+    };
+    if let Some(background) = args.svg_background {
+        svg_options.background = background;
+    }
+    if let Some(foreground) = args.svg_foreground {
+        svg_options.foreground = foreground;
+    }
+    let bbcode_wrapper = Some(args.bbcode_wrapper.as_str()).filter(|tag| !tag.is_empty());
+    if let Some(raw_theme) = &args.raw_theme {
+        let raw_theme = match RawTheme::embedded(raw_theme) {
+            Some(raw_theme) => raw_theme,
+            None => RawTheme::load(Path::new(raw_theme)).wrap_err_with(|| {
+                format!("failed to load raw theme `{raw_theme}` (see `--list-raw-themes` for embedded themes)")
+            })?,
+        };
+        highlighter.with_raw_theme(raw_theme);
+    }
+    if let Some(dir) = &args.syntax_dir {
+        let syntax_set = typst_ansi_hl::load_syntaxes(dir)
+            .wrap_err_with(|| format!("failed to load syntaxes from `{}`", dir.display()))?;
+        highlighter.with_syntax_set(syntax_set);
+    }
+    for (alias, lang) in &args.lang_aliases {
+        highlighter.with_lang_alias(alias, lang);
+    }
+    if args.guess_lang {
+        highlighter.with_lang_guessing(Some(args.guess_threshold));
+    }
+
     let mut input = String::new();
     if let Some(path) = &args.input {
         std::fs::File::open(path)
@@ -120,18 +415,32 @@ fn main() -> Result<()> {
         stripped = &input;
     }
 
-    let out = termcolor::Ansi::new(std::io::stdout().lock());
-    let mut highlighter = Highlighter::default();
-    if args.discord {
-        highlighter.for_discord();
+    for warning in highlighter.warnings(stripped) {
+        eprintln!("warning: {warning}");
     }
-    highlighter.with_syntax_mode(args.mode.into());
     if let Some(soft_limit) = args.soft_limit {
         highlighter.with_soft_limit(soft_limit);
     }
-    highlighter
-        .highlight_to(stripped, out)
-        .wrap_err("failed to highlight input")?;
+    let out = std::io::stdout().lock();
+    if args.matrix {
+        return highlighter
+            .highlight_matrix_to(stripped, out)
+            .wrap_err("failed to highlight input");
+    }
+    match args.format {
+        Format::Ansi => highlighter.highlight_to(stripped, termcolor::Ansi::new(out)),
+        Format::Html => highlighter.highlight_html_to(stripped, html_style, out),
+        Format::Json => highlighter.highlight_json_to(stripped, out),
+        Format::Svg => highlighter.highlight_svg_to(stripped, &svg_options, out),
+        Format::Latex => highlighter.highlight_latex_to(stripped, out),
+        Format::Rtf => highlighter.highlight_rtf_to(stripped, out),
+        Format::Irc => highlighter.highlight_irc_to(stripped, out),
+        Format::Bbcode => highlighter.highlight_bbcode_to(stripped, bbcode_wrapper, out),
+        Format::Pango => highlighter.highlight_pango_to(stripped, out),
+        Format::Typst => highlighter.highlight_typst_to(stripped, out),
+        Format::Unicode => highlighter.highlight_unicode_to(stripped, out),
+    }
+    .wrap_err("failed to highlight input")?;
 
     Ok(())
 }
@@ -190,4 +499,42 @@ mod tests {
         assert_eq!(unindent("    hello\n  world"), "  hello\nworld");
         assert_eq!(unindent("  hello\n \tworld"), " hello\n\tworld");
     }
+
+    #[test]
+    fn test_parse_confidence() {
+        assert_eq!(parse_confidence("0"), Ok(0.0));
+        assert_eq!(parse_confidence("0.75"), Ok(0.75));
+        assert_eq!(parse_confidence("1"), Ok(1.0));
+        assert!(parse_confidence("1.5").is_err());
+        assert!(parse_confidence("-0.1").is_err());
+        assert!(parse_confidence("NaN").is_err());
+        assert!(parse_confidence("half").is_err());
+    }
+
+    #[test]
+    fn test_detect_color_depth() {
+        use typst_ansi_hl::ColorDepth;
+
+        assert_eq!(
+            detect_color_depth("truecolor", "xterm"),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(detect_color_depth("24bit", ""), ColorDepth::TrueColor);
+        assert_eq!(
+            detect_color_depth("", "xterm-direct"),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            detect_color_depth("", "xterm-256color"),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            detect_color_depth("", "screen.xterm-256color"),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(detect_color_depth("", "linux"), ColorDepth::Ansi16);
+        assert_eq!(detect_color_depth("", "ansi"), ColorDepth::Ansi8);
+        assert_eq!(detect_color_depth("", "xterm"), ColorDepth::Ansi256);
+        assert_eq!(detect_color_depth("", ""), ColorDepth::Ansi256);
+    }
 }